@@ -0,0 +1,79 @@
+use lox::*;
+use std::error::Error;
+use std::fmt;
+use syntax::*;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "note"
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Label {
+    pub message: String,
+    pub position: Position
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub ty: LoxErrorTy,
+    pub code: ErrorCode,
+    pub message: String,
+    pub source_file: Option<String>,
+    pub position: Position,
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>
+}
+
+impl Diagnostic {
+    pub fn new(
+        ty: LoxErrorTy,
+        code: ErrorCode,
+        message: &str,
+        source_file: &Option<String>,
+        position: Position
+    ) -> Diagnostic {
+        Diagnostic {
+            severity: Severity::Error,
+            ty,
+            code,
+            message: message.to_string(),
+            source_file: source_file.clone(),
+            position,
+            labels: Vec::new(),
+            notes: Vec::new()
+        }
+    }
+
+    pub fn with_label(mut self, message: &str, position: Position) -> Diagnostic {
+        self.labels.push(Label { message: message.to_string(), position });
+        self
+    }
+
+    pub fn with_note(mut self, note: &str) -> Diagnostic {
+        self.notes.push(note.to_string());
+        self
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[{}]: {}", self.ty, self.code, self.message)
+    }
+}
+
+impl Error for Diagnostic {}
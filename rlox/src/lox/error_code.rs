@@ -0,0 +1,63 @@
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ErrorCode {
+    UnexpectedCharacter,
+    UnterminatedString,
+    UnterminatedInterpolation,
+    UnterminatedComment,
+    InvalidEscape,
+    InvalidNumber,
+    ExpectedToken,
+    InvalidAssignmentTarget,
+    TooManyArguments,
+    ExpectedExpression,
+    DuplicateDeclaration,
+    ReadInInitializer,
+    TopLevelReturn,
+    InitializerReturn,
+    InvalidThis,
+    InvalidSuper,
+    InheritsFromItself,
+    UndefinedVariable,
+    UndefinedProperty,
+    InvalidOperand,
+    NotCallable,
+    ArityMismatch,
+    StackOverflow,
+    InvalidSuperclass,
+    NotAnInstance
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            ErrorCode::UnexpectedCharacter => "E0001",
+            ErrorCode::UnterminatedString => "E0002",
+            ErrorCode::UnterminatedInterpolation => "E0003",
+            ErrorCode::UnterminatedComment => "E0004",
+            ErrorCode::InvalidEscape => "E0005",
+            ErrorCode::InvalidNumber => "E0006",
+            ErrorCode::ExpectedToken => "E0100",
+            ErrorCode::InvalidAssignmentTarget => "E0101",
+            ErrorCode::TooManyArguments => "E0102",
+            ErrorCode::ExpectedExpression => "E0103",
+            ErrorCode::DuplicateDeclaration => "E0200",
+            ErrorCode::ReadInInitializer => "E0201",
+            ErrorCode::TopLevelReturn => "E0202",
+            ErrorCode::InitializerReturn => "E0203",
+            ErrorCode::InvalidThis => "E0204",
+            ErrorCode::InvalidSuper => "E0205",
+            ErrorCode::InheritsFromItself => "E0206",
+            ErrorCode::UndefinedVariable => "E0300",
+            ErrorCode::UndefinedProperty => "E0301",
+            ErrorCode::InvalidOperand => "E0302",
+            ErrorCode::NotCallable => "E0303",
+            ErrorCode::ArityMismatch => "E0304",
+            ErrorCode::StackOverflow => "E0305",
+            ErrorCode::InvalidSuperclass => "E0306",
+            ErrorCode::NotAnInstance => "E0307"
+        };
+        write!(f, "{}", s)
+    }
+}
@@ -0,0 +1,80 @@
+use lox::*;
+use syntax::*;
+
+pub struct JsonRenderer;
+
+impl JsonRenderer {
+    fn string(s: &str) -> String {
+        let mut escaped = String::with_capacity(s.len() + 2);
+
+        escaped.push('"');
+        for c in s.chars() {
+            match c {
+                '"' => escaped.push_str("\\\""),
+                '\\' => escaped.push_str("\\\\"),
+                '\n' => escaped.push_str("\\n"),
+                '\r' => escaped.push_str("\\r"),
+                '\t' => escaped.push_str("\\t"),
+                c if (c as u32) < 0x20 => {
+                    escaped.push_str(&format!("\\u{:04x}", c as u32))
+                },
+                c => escaped.push(c)
+            }
+        }
+        escaped.push('"');
+
+        escaped
+    }
+}
+
+impl Renderer for JsonRenderer {
+    fn render(&self, diagnostic: &Diagnostic, _source_map: &SourceMap) -> String {
+        let position = diagnostic.position;
+        let file = match diagnostic.source_file {
+            Some(ref s) => Self::string(s),
+            None => "null".to_string()
+        };
+
+        format!(
+            "{{\"type\":{},\"code\":{},\"message\":{},\"file\":{},\"line\":{},\"column\":{},\"offset\":{},\"length\":{}}}",
+            Self::string(&diagnostic.ty.to_string()),
+            Self::string(&diagnostic.code.to_string()),
+            Self::string(&diagnostic.message),
+            file,
+            position.line + 1,
+            position.column + 1,
+            position.offset,
+            position.length
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_renders_json_diagnostics() {
+        let source_map = SourceMap::new("print 1;\n\"\\\tx");
+        let diagnostic = Diagnostic::new(
+            LoxErrorTy::Syntax,
+            ErrorCode::UnterminatedString,
+            "Unterminated string. Expected `\"`",
+            &None,
+            source_map.position(3, 9)
+        );
+
+        assert_eq!(
+            JsonRenderer.render(&diagnostic, &source_map),
+            "{\"type\":\"SyntaxError\",\"code\":\"E0002\",\"message\":\"Unterminated string. Expected `\\\"`\",\"file\":null,\"line\":2,\"column\":1,\"offset\":9,\"length\":3}"
+        );
+    }
+
+    #[test]
+    fn it_escapes_json_strings() {
+        assert_eq!(
+            JsonRenderer::string("a\"b\\c\nd\u{1}"),
+            "\"a\\\"b\\\\c\\nd\\u0001\""
+        );
+    }
+}
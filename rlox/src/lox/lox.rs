@@ -1,35 +1,82 @@
+use lox::*;
 use runtime::*;
 use rustyline::error::ReadlineError;
 use rustyline::Editor;
+use std::fs;
+use std::time::Instant;
 use syntax::*;
 use yansi::Color;
 
+const HELP: &str = ":help          Show this message
+:quit          Exit the REPL
+:reset         Discard all definitions and start a fresh interpreter
+:env           List global bindings with their types and values
+:load <file>   Run a script in the current session
+:tokens <src>  Show the tokens scanned from <src>
+:ast <src>     Show the syntax tree parsed from <src>
+:time <src>    Run <src> and report how long it took";
+
 pub struct Lox<'a> {
-    editor: Editor<()>,
+    editor: Editor<LoxHelper>,
     prompt: &'a str,
     history_file: &'a str,
+    source_file: Option<String>,
+    repl: bool,
+    renderer: Box<dyn Renderer>,
     interpreter: Interpreter
 }
 
 impl<'a> Lox<'a> {
     pub fn new() -> Lox<'a> {
+        let mut editor = Editor::<LoxHelper>::new();
+        editor.set_helper(Some(LoxHelper::new()));
+
         Lox {
-            editor: Editor::<()>::new(),
+            editor,
             prompt: ">> ",
             history_file: ".rlox_history",
+            source_file: None,
+            repl: false,
+            renderer: Box::new(PrettyRenderer::new()),
             interpreter: Interpreter::new()
         }
     }
 
+    pub fn run_file(&mut self, path: &str) -> i32 {
+        match fs::read_to_string(path) {
+            Ok(source) => {
+                self.source_file = Some(path.to_string());
+                self.interpreter.source_file = Some(path.to_string());
+
+                match self.interpret(&source) {
+                    Ok(_) => 0,
+                    Err(diagnostics) => self.report(&diagnostics, &source)
+                }
+            },
+            Err(err) => {
+                eprintln!("{}: {}: {}", Color::Red.paint("IOError"), path, err);
+                66
+            }
+        }
+    }
+
     pub fn interactive(&mut self) {
+        self.repl = true;
         self.editor.load_history(self.history_file).unwrap_or(());
         loop {
             match self.editor.readline(self.prompt) {
-                Ok(line) => {
-                    self.interpret(&line);
-                    self.editor.add_history_entry(line);
+                Ok(input) => {
+                    self.editor.add_history_entry(input.as_str());
+                    if input.trim_start().starts_with(':') {
+                        if !self.run_command(input.trim()) {
+                            break;
+                        }
+                    } else {
+                        self.evaluate(&input);
+                    }
+                    self.update_names();
                 },
-                Err(ReadlineError::Interrupted) => break,
+                Err(ReadlineError::Interrupted) => continue,
                 Err(ReadlineError::Eof) => break,
                 Err(err) => {
                     println!("{}: {}", Color::Red.paint("ReadlineError"), err);
@@ -40,17 +87,241 @@ impl<'a> Lox<'a> {
         self.editor.save_history(self.history_file).unwrap_or(());
     }
 
-    pub fn interpret(&mut self, source: &str) {
-        self.interpreter.reset();
-        match Scanner::new(source).scan_tokens() {
-            Ok(tokens) => match Parser::new(tokens, source).parse() {
-                Ok(stmts) => match self.interpreter.interpret(stmts, source) {
+    fn update_names(&mut self) {
+        let names = self.interpreter.globals()
+                                    .borrow()
+                                    .bindings()
+                                    .into_iter()
+                                    .map(|(name, _)| name)
+                                    .collect();
+        if let Some(helper) = self.editor.helper_mut() {
+            helper.names = names;
+        }
+    }
+
+    fn evaluate(&mut self, source: &str) {
+        match self.interpret(source) {
+            Ok(Some(LoxObject::Nil)) | Ok(None) => {},
+            Ok(Some(value)) => println!("{}", value),
+            Err(diagnostics) => {
+                self.report(&diagnostics, source);
+            }
+        }
+    }
+
+    fn run_command(&mut self, input: &str) -> bool {
+        let (command, argument) = match input.find(char::is_whitespace) {
+            Some(i) => (&input[..i], input[i..].trim()),
+            None => (input, "")
+        };
+
+        match command {
+            ":help" => println!("{}", HELP),
+            ":quit" => return false,
+            ":reset" => self.interpreter = Interpreter::new(),
+            ":env" => {
+                for (name, value) in self.interpreter.globals().borrow().bindings() {
+                    println!("{}: {} = {}", name, value.type_name(), value);
+                }
+            },
+            ":load" => {
+                self.repl = false;
+                self.run_file(argument);
+                self.repl = true;
+                self.source_file = None;
+                self.interpreter.source_file = None;
+            },
+            ":tokens" => {
+                let mut scanner = Scanner::new(argument);
+                for token in scanner.scan() {
+                    let position = token.position;
+                    println!(
+                        "{}:{} {:?}",
+                        position.line + 1,
+                        position.column + 1,
+                        token.ty
+                    );
+                }
+                self.report(&scanner.errors(), argument);
+            },
+            ":ast" => {
+                let mut scanner = Scanner::new(argument);
+                let mut parser = Parser::new(scanner.scan(), argument);
+                parser.repl = true;
+                let parsed = parser.parse();
+
+                let mut diagnostics = scanner.errors();
+                match parsed {
+                    Ok(ref stmts) if diagnostics.is_empty() => for stmt in stmts {
+                        AstPrinter::new().print_stmt(stmt);
+                    },
                     Ok(_) => {},
-                    Err(err) => println!("{}", err)
-                },
-                Err(err) => println!("{}", err)
+                    Err(errs) => diagnostics.extend(errs)
+                }
+                if !diagnostics.is_empty() {
+                    diagnostics.sort_by_key(|diagnostic| diagnostic.position.offset);
+                    self.report(&diagnostics, argument);
+                }
             },
-            Err(err) => println!("{}", err)
+            ":time" => {
+                let start = Instant::now();
+                self.evaluate(argument);
+                println!("{:?}", start.elapsed());
+            },
+            _ => eprintln!(
+                "{}: Unknown command `{}`. Type `:help` for a list of commands.",
+                Color::Red.paint("CommandError"),
+                command
+            )
+        }
+
+        true
+    }
+
+    pub fn interpret(
+        &mut self,
+        source: &str
+    ) -> Result<Option<LoxObject>, Vec<Diagnostic>> {
+        self.interpreter.reset();
+
+        let mut scanner = Scanner::new(source);
+        scanner.source_file = self.source_file.clone();
+        let tokens = scanner.scan();
+
+        let mut parser = Parser::new(tokens, source);
+        parser.source_file = self.source_file.clone();
+        parser.repl = self.repl;
+        let parsed = parser.parse();
+
+        let mut diagnostics = scanner.errors();
+        let stmts = match parsed {
+            Ok(stmts) => stmts,
+            Err(errs) => {
+                diagnostics.extend(errs);
+                Vec::new()
+            }
+        };
+        if !diagnostics.is_empty() {
+            diagnostics.sort_by_key(|diagnostic| diagnostic.position.offset);
+            return Err(diagnostics);
+        }
+
+        let mut resolver = Resolver::new();
+        resolver.source_file = self.source_file.clone();
+        let locals = resolver.resolve(&stmts).map_err(|err| vec![*err])?;
+
+        self.interpreter.resolve(locals);
+        self.interpreter.interpret(stmts).map_err(|err| vec![*err])
+    }
+
+    pub fn set_renderer(&mut self, renderer: Box<dyn Renderer>) {
+        self.renderer = renderer;
+    }
+
+    fn report(&self, diagnostics: &[Diagnostic], source: &str) -> i32 {
+        let source_map = SourceMap::new(source);
+        let mut ty = LoxErrorTy::Syntax;
+        for diagnostic in diagnostics {
+            eprintln!("{}", self.renderer.render(diagnostic, &source_map));
+            ty = diagnostic.ty;
         }
+        ty.exit_code()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn evaluate(lox: &mut Lox, source: &str) -> String {
+        match lox.interpret(source) {
+            Ok(Some(value)) => value.to_string(),
+            Ok(None) => String::new(),
+            Err(diagnostics) => diagnostics[0].message.clone()
+        }
+    }
+
+    fn repl() -> Lox<'static> {
+        let mut lox = Lox::new();
+        lox.repl = true;
+        lox
+    }
+
+    #[test]
+    fn it_echoes_bare_expressions() {
+        let mut lox = repl();
+        assert_eq!(evaluate(&mut lox, "1 + 2"), "3");
+        assert_eq!(evaluate(&mut lox, "\"a\" + \"b\""), "ab");
+        assert_eq!(evaluate(&mut lox, "print 1;"), "");
+    }
+
+    #[test]
+    fn it_keeps_state_after_runtime_errors() {
+        let mut lox = repl();
+        evaluate(&mut lox, "var a = 1;");
+        assert!(lox.interpret("a - \"x\";").is_err());
+        assert_eq!(evaluate(&mut lox, "a"), "1");
+    }
+
+    #[test]
+    fn it_skips_side_effects_of_failed_expressions() {
+        let mut lox = repl();
+        evaluate(&mut lox, "var a = 1; var c = 0; class I {} var i = I(); i.f = 1;");
+
+        assert!(lox.interpret("a = -\"x\";").is_err());
+        assert_eq!(evaluate(&mut lox, "a"), "1");
+
+        assert!(lox.interpret("var b = -\"x\";").is_err());
+        let diagnostics = lox.interpret("b").unwrap_err();
+        assert_eq!(diagnostics[0].code, ErrorCode::UndefinedVariable);
+
+        assert!(lox.interpret("i.f = -\"x\";").is_err());
+        assert_eq!(evaluate(&mut lox, "i.f"), "1");
+
+        assert!(lox.interpret("(-\"x\") + (c = 5);").is_err());
+        assert!(lox.interpret("(-\"x\") or (c = 6);").is_err());
+        assert!(lox.interpret("(-\"x\")(c = 7);").is_err());
+        assert_eq!(evaluate(&mut lox, "c"), "0");
+    }
+
+    #[test]
+    fn it_loads_files_as_scripts() {
+        let path = env::temp_dir().join("rlox_load_test.lox");
+        let command = format!(":load {}", path.display());
+        let mut lox = repl();
+
+        fs::write(&path, "var z = 1;\nz + 1").expect("");
+        lox.run_command(&command);
+        let diagnostics = lox.interpret("z").unwrap_err();
+        assert_eq!(diagnostics[0].code, ErrorCode::UndefinedVariable);
+
+        fs::write(&path, "var z = 1;\nz + 1;").expect("");
+        lox.run_command(&command);
+        assert_eq!(evaluate(&mut lox, "z"), "1");
+        assert!(lox.repl);
+
+        fs::remove_file(&path).expect("");
+    }
+
+    #[test]
+    fn it_returns_to_globals_after_errors_in_blocks() {
+        let mut lox = repl();
+        assert!(lox.interpret("{ var b = 1; b - \"x\"; }").is_err());
+        let diagnostics = lox.interpret("b").unwrap_err();
+        assert_eq!(diagnostics[0].code, ErrorCode::UndefinedVariable);
+        evaluate(&mut lox, "var d = 2;");
+        assert_eq!(evaluate(&mut lox, "d"), "2");
+    }
+
+    #[test]
+    fn it_returns_to_globals_after_errors_in_functions() {
+        let mut lox = repl();
+        evaluate(&mut lox, "fun f(c) { c - \"x\"; }");
+        assert!(lox.interpret("f(1);").is_err());
+        let diagnostics = lox.interpret("c").unwrap_err();
+        assert_eq!(diagnostics[0].code, ErrorCode::UndefinedVariable);
+        evaluate(&mut lox, "var d = 2;");
+        assert_eq!(evaluate(&mut lox, "d"), "2");
     }
 }
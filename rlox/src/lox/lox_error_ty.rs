@@ -1,11 +1,20 @@
 use std::fmt;
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum LoxErrorTy {
     Runtime,
     Syntax
 }
 
+impl LoxErrorTy {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            LoxErrorTy::Runtime => 70,
+            LoxErrorTy::Syntax => 65
+        }
+    }
+}
+
 impl fmt::Display for LoxErrorTy {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let s = match self {
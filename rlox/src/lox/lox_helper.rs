@@ -0,0 +1,200 @@
+use rustyline::completion::Completer;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::{ValidationContext, ValidationResult, Validator};
+use rustyline::{Context, Helper, Result};
+use std::borrow::Cow;
+use syntax::*;
+use unicode_xid::UnicodeXID;
+use yansi::Color;
+
+type Span = (usize, usize, Color);
+
+pub struct LoxHelper {
+    pub names: Vec<String>
+}
+
+impl LoxHelper {
+    pub fn new() -> LoxHelper {
+        LoxHelper { names: Vec::new() }
+    }
+
+    pub fn candidates(&self, line: &str, pos: usize) -> (usize, Vec<String>) {
+        let start = line[..pos].char_indices()
+                               .rev()
+                               .take_while(|(_, c)| *c == '_' || c.is_xid_continue())
+                               .last()
+                               .map_or(pos, |(i, _)| i);
+        let prefix = &line[start..pos];
+        if prefix.is_empty() || prefix.starts_with(|c: char| c.is_ascii_digit()) {
+            return (pos, Vec::new());
+        }
+
+        let mut candidates: Vec<String> =
+            KEYWORDS.iter()
+                    .map(|(keyword, _)| keyword.to_string())
+                    .chain(self.names.iter().cloned())
+                    .filter(|name| name.starts_with(prefix))
+                    .collect();
+        candidates.sort();
+        candidates.dedup();
+
+        (start, candidates)
+    }
+
+    pub fn is_incomplete(source: &str) -> bool {
+        if source.trim_start().starts_with(':') {
+            return false;
+        }
+
+        let mut scanner = Scanner::new(source);
+        let tokens = scanner.scan();
+        let depth = tokens.iter().fold(0, |depth: i32, token| match token.ty {
+            TokenTy::LeftBrace | TokenTy::LeftParen => depth + 1,
+            TokenTy::RightBrace | TokenTy::RightParen => depth - 1,
+            _ => depth
+        });
+
+        scanner.is_unterminated() || depth > 0
+    }
+
+    fn spans(line: &str) -> Vec<Span> {
+        let mut spans = Vec::new();
+        let mut last = 0;
+        let comment = |spans: &mut Vec<Span>, from: usize, to: usize| {
+            let gap = &line[from..to];
+            let text = gap.trim();
+            if !text.is_empty() {
+                let start = from + gap.find(text).unwrap_or(0);
+                spans.push((start, start + text.len(), Color::Fixed(244)));
+            }
+        };
+
+        for token in Scanner::new(line).scan() {
+            let Position { offset, length, .. } = token.position;
+            if token.ty == TokenTy::Eof || offset < last {
+                continue;
+            }
+            comment(&mut spans, last, offset);
+            last = offset + length;
+
+            let color = match token.ty {
+                TokenTy::String(_) | TokenTy::Interpolation(_) => Color::Green,
+                TokenTy::Number(_) => Color::Yellow,
+                TokenTy::Error if line[offset..].starts_with("/*") => Color::Fixed(244),
+                TokenTy::Error => Color::Red,
+                ref ty if KEYWORDS.iter().any(|(_, keyword)| keyword == ty) => {
+                    Color::Magenta
+                },
+                _ => continue
+            };
+            spans.push((offset, last, color));
+        }
+        comment(&mut spans, last, line.len());
+
+        spans
+    }
+}
+
+impl Completer for LoxHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>
+    ) -> Result<(usize, Vec<String>)> {
+        Ok(self.candidates(line, pos))
+    }
+}
+
+impl Highlighter for LoxHelper {
+    fn highlight<'l>(&self, line: &'l str, _pos: usize) -> Cow<'l, str> {
+        let mut highlighted = String::with_capacity(line.len());
+        let mut last = 0;
+
+        for (start, end, color) in Self::spans(line) {
+            highlighted.push_str(&line[last..start]);
+            highlighted.push_str(&color.paint(&line[start..end]).to_string());
+            last = end;
+        }
+        highlighted.push_str(&line[last..]);
+
+        Cow::Owned(highlighted)
+    }
+
+    fn highlight_char(&self, _line: &str, _pos: usize) -> bool {
+        true
+    }
+}
+
+impl Hinter for LoxHelper {
+    type Hint = String;
+}
+
+impl Validator for LoxHelper {
+    fn validate(&self, ctx: &mut ValidationContext) -> Result<ValidationResult> {
+        if Self::is_incomplete(ctx.input()) {
+            Ok(ValidationResult::Incomplete)
+        } else {
+            Ok(ValidationResult::Valid(None))
+        }
+    }
+}
+
+impl Helper for LoxHelper {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_completes_keywords_and_names() {
+        let mut helper = LoxHelper::new();
+        helper.names = vec!["counter".to_string(), "fib".to_string()];
+
+        assert_eq!(
+            helper.candidates("print f", 7),
+            (6, vec![
+                "false".to_string(),
+                "fib".to_string(),
+                "for".to_string(),
+                "fun".to_string()
+            ])
+        );
+        assert_eq!(helper.candidates("co + 1", 2), (0, vec!["counter".to_string()]));
+        assert_eq!(helper.candidates("1 + ", 4), (4, vec![]));
+    }
+
+    #[test]
+    fn it_detects_incomplete_input() {
+        assert!(LoxHelper::is_incomplete("fun f() {"));
+        assert!(LoxHelper::is_incomplete("print (1 +"));
+        assert!(LoxHelper::is_incomplete("print \"a"));
+        assert!(LoxHelper::is_incomplete("/* comment"));
+        assert!(LoxHelper::is_incomplete("class A { f() { print \"${1}\"; }"));
+        assert!(!LoxHelper::is_incomplete("fun f() {}"));
+        assert!(!LoxHelper::is_incomplete("print 1;"));
+        assert!(!LoxHelper::is_incomplete("}"));
+        assert!(!LoxHelper::is_incomplete(":ast print \"abc"));
+    }
+
+    #[test]
+    fn it_highlights_tokens_by_category() {
+        assert_eq!(
+            LoxHelper::spans("var s = \"a${1}\"; // done"),
+            vec![
+                (0, 3, Color::Magenta),
+                (8, 12, Color::Green),
+                (12, 13, Color::Yellow),
+                (13, 15, Color::Green),
+                (17, 24, Color::Fixed(244))
+            ]
+        );
+        assert_eq!(
+            LoxHelper::spans("nil /* open"),
+            vec![(0, 3, Color::Magenta), (4, 11, Color::Fixed(244))]
+        );
+    }
+}
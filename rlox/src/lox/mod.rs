@@ -1,7 +1,19 @@
+#![allow(clippy::module_inception)]
+
+pub mod diagnostic;
+pub mod error_code;
+pub mod json_renderer;
 pub mod lox;
-pub mod lox_error;
+pub mod lox_helper;
 pub mod lox_error_ty;
+pub mod pretty_renderer;
+pub mod renderer;
 
+pub use self::diagnostic::*;
+pub use self::error_code::*;
+pub use self::json_renderer::*;
 pub use self::lox::*;
-pub use self::lox_error::*;
+pub use self::lox_helper::*;
 pub use self::lox_error_ty::*;
+pub use self::pretty_renderer::*;
+pub use self::renderer::*;
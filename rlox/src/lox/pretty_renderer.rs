@@ -0,0 +1,244 @@
+use lox::*;
+use std::cmp;
+use syntax::*;
+use yansi::Color;
+
+type Span = ((usize, usize), (usize, usize));
+
+pub struct PrettyRenderer {
+    pub context: usize
+}
+
+impl PrettyRenderer {
+    pub fn new() -> PrettyRenderer {
+        PrettyRenderer { context: 1 }
+    }
+
+    pub fn with_context(context: usize) -> PrettyRenderer {
+        PrettyRenderer { context }
+    }
+
+    fn span(source_map: &SourceMap, position: Position) -> Span {
+        let start = (position.line, position.column);
+        let end =
+            if position.length == 0 {
+                start
+            } else if position.end_column > 0 {
+                (position.end_line, position.end_column - 1)
+            } else {
+                let line = position.end_line - 1;
+                (line, source_map.line(line).chars().count())
+            };
+        (start, end)
+    }
+
+    fn gutter(width: usize, line: Option<usize>) -> String {
+        match line {
+            Some(line) => format!("{:>width$} | ", line + 1, width = width),
+            None => format!("{:>width$} | ", "", width = width)
+        }
+    }
+
+    fn snippet(
+        source_map: &SourceMap,
+        position: Span,
+        width: usize,
+        color: Color,
+        label: &str
+    ) -> Vec<String> {
+        let ((start_line, start_col), (end_line, end_col)) = position;
+        let mut snippet = Vec::new();
+
+        for line in start_line..=end_line {
+            let text = source_map.line(line);
+            let from = if line == start_line { start_col } else { 0 };
+            let to =
+                if line == end_line {
+                    end_col + 1
+                } else {
+                    text.chars().count()
+                };
+            let to = cmp::max(to, from + 1);
+            let mut pointer = format!(
+                "{}{}",
+                "-".repeat(width + 3 + from),
+                "^".repeat(to - from)
+            );
+            if line == end_line && !label.is_empty() {
+                pointer.push(' ');
+                pointer.push_str(label);
+            }
+
+            snippet.push(format!(
+                "{}{}",
+                Color::Blue.paint(Self::gutter(width, Some(line))),
+                text
+            ));
+            snippet.push(color.paint(pointer).to_string());
+        }
+
+        snippet
+    }
+}
+
+impl Renderer for PrettyRenderer {
+    fn render(&self, diagnostic: &Diagnostic, source_map: &SourceMap) -> String {
+        let span = Self::span(source_map, diagnostic.position);
+        let ((err_line, err_col), (end_line, _)) = span;
+        let first_line = err_line.saturating_sub(self.context);
+        let last_line = cmp::min(end_line + self.context, source_map.line_count() - 1);
+        let labels: Vec<(&Label, Span)> =
+            diagnostic.labels.iter()
+                             .map(|label| (label, Self::span(source_map, label.position)))
+                             .collect();
+        let width = labels.iter()
+                          .map(|(_, (_, (line, _)))| *line + 1)
+                          .chain(Some(last_line + 1))
+                          .max()
+                          .unwrap_or(1)
+                          .to_string()
+                          .len();
+
+        let error = match diagnostic.severity {
+            Severity::Error => Color::Red.paint(diagnostic.ty.to_string()),
+            severity => Color::Yellow.paint(severity.to_string())
+        };
+        let file = match diagnostic.source_file {
+            Some(ref s) => format!("{}:", s),
+            None => "".to_string()
+        };
+        let mut output = vec![
+            format!("{}[{}]: {}", error, diagnostic.code, diagnostic.message),
+            format!(
+                "{}{}{}:{}",
+                Color::Blue.paint(format!("{}> ", "-".repeat(width + 1))),
+                file,
+                err_line + 1,
+                err_col + 1
+            )
+        ];
+
+        let context = |line: usize| format!(
+            "{}{}",
+            Color::Blue.paint(Self::gutter(width, Some(line))),
+            source_map.line(line)
+        );
+        output.extend((first_line..err_line).map(context));
+        output.extend(Self::snippet(source_map, span, width, Color::Red, ""));
+        output.extend(((end_line + 1)..=last_line).map(context));
+
+        for (label, span) in labels {
+            output.extend(
+                Self::snippet(source_map, span, width, Color::Blue, &label.message)
+            );
+        }
+
+        for note in &diagnostic.notes {
+            output.push(format!(
+                "{}= {}: {}",
+                Color::Blue.paint(Self::gutter(width, None)),
+                Severity::Note,
+                note
+            ));
+        }
+
+        output.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yansi::Paint;
+
+    fn render(renderer: PrettyRenderer, diagnostic: Diagnostic, source: &str) -> String {
+        Paint::disable();
+        renderer.render(&diagnostic, &SourceMap::new(source))
+    }
+
+    #[test]
+    fn it_underlines_the_whole_span() {
+        let source = "var a;\nprint a;\nprint 1 - \"a\";\n";
+        let diagnostic = Diagnostic::new(
+            LoxErrorTy::Runtime,
+            ErrorCode::InvalidOperand,
+            "Operands must be numbers.",
+            &Some("main.lox".to_string()),
+            SourceMap::new(source).position(7, 16)
+        );
+
+        assert_eq!(
+            render(PrettyRenderer::new(), diagnostic, source),
+            "RuntimeError[E0302]: Operands must be numbers.
+--> main.lox:3:1
+2 | print a;
+3 | print 1 - \"a\";
+----^^^^^^^
+4 | "
+        );
+    }
+
+    #[test]
+    fn it_underlines_spans_across_lines() {
+        let source = "a;\n(b +\n c) = 1;";
+        let diagnostic = Diagnostic::new(
+            LoxErrorTy::Syntax,
+            ErrorCode::InvalidAssignmentTarget,
+            "Invalid assignment target.",
+            &None,
+            SourceMap::new(source).position(8, 3)
+        );
+
+        assert_eq!(
+            render(PrettyRenderer::with_context(0), diagnostic, source),
+            "SyntaxError[E0101]: Invalid assignment target.
+--> 2:1
+2 | (b +
+----^^^^
+3 |  c) = 1;
+----^^^"
+        );
+    }
+
+    #[test]
+    fn it_aligns_underlines_with_characters() {
+        let source = "print \"é\" 🦀;";
+        let diagnostic = Diagnostic::new(
+            LoxErrorTy::Syntax,
+            ErrorCode::UnexpectedCharacter,
+            "Unexpected character `🦀`.",
+            &None,
+            SourceMap::new(source).position(4, 11)
+        );
+
+        assert_eq!(
+            render(PrettyRenderer::with_context(0), diagnostic, source),
+            "SyntaxError[E0001]: Unexpected character `🦀`.
+--> 1:11
+1 | print \"é\" 🦀;
+--------------^"
+        );
+    }
+
+    #[test]
+    fn it_renders_labels_and_notes() {
+        let source = "{\n  var a = 1;\n  var a = 2;\n}";
+        let stmts = Parser::new(Scanner::new(source).scan(), source).parse().expect("");
+        let mut resolver = Resolver::new();
+        resolver.source_file = Some("main.lox".to_string());
+        let diagnostic = resolver.resolve(&stmts)
+                                 .expect_err("")
+                                 .with_note("Shadowing is only allowed in nested scopes.");
+
+        assert_eq!(
+            render(PrettyRenderer::with_context(0), diagnostic, source),
+            "SyntaxError[E0200]: Variable `a` already declared in this scope.
+--> main.lox:3:7
+3 |   var a = 2;
+----------^
+2 |   var a = 1;
+----------^ previously declared here
+  | = note: Shadowing is only allowed in nested scopes."
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use lox::*;
+use syntax::*;
+
+pub trait Renderer {
+    fn render(&self, diagnostic: &Diagnostic, source_map: &SourceMap) -> String;
+}
+
+pub struct ShortRenderer;
+
+impl Renderer for ShortRenderer {
+    fn render(&self, diagnostic: &Diagnostic, _source_map: &SourceMap) -> String {
+        let position = diagnostic.position;
+        let file = match diagnostic.source_file {
+            Some(ref s) => format!("{}:", s),
+            None => "".to_string()
+        };
+
+        format!(
+            "{}{}:{}: {}",
+            file,
+            position.line + 1,
+            position.column + 1,
+            diagnostic
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_renders_short_diagnostics() {
+        let source_map = SourceMap::new("1;\n1 @");
+        let diagnostic = Diagnostic::new(
+            LoxErrorTy::Syntax,
+            ErrorCode::UnexpectedCharacter,
+            "Unexpected character `@`.",
+            &Some("main.lox".to_string()),
+            source_map.position(1, 4)
+        );
+
+        assert_eq!(
+            ShortRenderer.render(&diagnostic, &source_map),
+            "main.lox:2:2: SyntaxError[E0001]: Unexpected character `@`."
+        );
+
+        let source_map = SourceMap::new("print \"é\" @");
+        let diagnostic = Diagnostic::new(
+            LoxErrorTy::Syntax,
+            ErrorCode::UnexpectedCharacter,
+            "Unexpected character `@`.",
+            &None,
+            source_map.position(1, 11)
+        );
+
+        assert_eq!(
+            ShortRenderer.render(&diagnostic, &source_map),
+            "1:11: SyntaxError[E0001]: Unexpected character `@`."
+        );
+    }
+}
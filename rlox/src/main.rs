@@ -1,18 +1,76 @@
 #![allow(dead_code)]
+#![allow(clippy::vec_box)]
 
 extern crate rustyline;
+extern crate unicode_xid;
 extern crate yansi;
 
 mod lox;
 mod runtime;
 mod syntax;
 
-use lox::Lox;
+use lox::*;
+use std::env;
+use std::io::{self, IsTerminal};
+use std::process;
+use std::thread;
+use yansi::Paint;
+
+const STACK_SIZE: usize = 64 * 1024 * 1024;
+
+fn usage() -> ! {
+    eprintln!(
+        "Usage: rlox [--error-format=pretty|short|json] [--context-lines=N] [script]"
+    );
+    process::exit(64);
+}
 
 fn main() {
-    Lox::new().interpret("
-        var a = 1;
-        var b = 2;
-        print a + b + c;
-    ");
+    // Lox calls recurse through the tree-walking interpreter, so give it
+    // enough stack to reach `MAX_CALL_DEPTH` before Rust's own limit.
+    let interpreter = thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(run)
+        .expect("failed to spawn the interpreter thread");
+
+    if interpreter.join().is_err() {
+        process::exit(101);
+    }
+}
+
+fn run() {
+    let mut lox = Lox::new();
+    let mut scripts: Vec<String> = Vec::new();
+    let mut format = "pretty".to_string();
+    let mut context = 1;
+
+    if env::var_os("NO_COLOR").is_some() || !io::stderr().is_terminal() {
+        Paint::disable();
+    }
+
+    for arg in env::args().skip(1) {
+        if let Some(value) = arg.strip_prefix("--error-format=") {
+            format = value.to_string();
+        } else if let Some(value) = arg.strip_prefix("--context-lines=") {
+            context = value.parse().unwrap_or_else(|_| usage());
+        } else if arg.starts_with('-') {
+            usage();
+        } else {
+            scripts.push(arg);
+        }
+    }
+
+    let renderer: Box<dyn Renderer> = match format.as_str() {
+        "pretty" => Box::new(PrettyRenderer::with_context(context)),
+        "short" => Box::new(ShortRenderer),
+        "json" => Box::new(JsonRenderer),
+        _ => usage()
+    };
+    lox.set_renderer(renderer);
+
+    match scripts.len() {
+        0 => lox.interactive(),
+        1 => process::exit(lox.run_file(&scripts[0])),
+        _ => usage()
+    }
 }
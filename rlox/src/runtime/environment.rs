@@ -1,22 +1,91 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::rc::Rc;
 use runtime::*;
 
 pub struct Environment {
-    values: HashMap<String, LoxObject>
+    values: HashMap<String, LoxObject>,
+    enclosing: Option<Rc<RefCell<Environment>>>
 }
 
 impl Environment {
     pub fn new() -> Environment {
         Environment {
-            values: HashMap::new()
+            values: HashMap::new(),
+            enclosing: None
         }
     }
 
-    pub fn get(&mut self, name: String) -> Option<&LoxObject> {
-        self.values.get(&name)
+    pub fn new_enclosed(enclosing: Rc<RefCell<Environment>>) -> Environment {
+        Environment {
+            values: HashMap::new(),
+            enclosing: Some(enclosing)
+        }
+    }
+
+    pub fn get(&self, name: String) -> Option<LoxObject> {
+        match self.values.get(&name) {
+            Some(value) => Some(value.clone()),
+            None => match self.enclosing {
+                Some(ref enclosing) => enclosing.borrow().get(name),
+                None => None
+            }
+        }
+    }
+
+    pub fn get_at(&self, distance: usize, name: String) -> Option<LoxObject> {
+        if distance == 0 {
+            self.values.get(&name).cloned()
+        } else {
+            match self.enclosing {
+                Some(ref enclosing) => {
+                    enclosing.borrow().get_at(distance - 1, name)
+                },
+                None => None
+            }
+        }
+    }
+
+    pub fn assign(&mut self, name: String, value: LoxObject) -> bool {
+        if let Some(slot) = self.values.get_mut(&name) {
+            *slot = value;
+            true
+        } else {
+            match self.enclosing {
+                Some(ref enclosing) => enclosing.borrow_mut().assign(name, value),
+                None => false
+            }
+        }
+    }
+
+    pub fn assign_at(
+        &mut self,
+        distance: usize,
+        name: String,
+        value: LoxObject
+    ) -> bool {
+        if distance == 0 {
+            self.assign(name, value)
+        } else {
+            match self.enclosing {
+                Some(ref enclosing) => {
+                    enclosing.borrow_mut().assign_at(distance - 1, name, value)
+                },
+                None => false
+            }
+        }
     }
 
     pub fn define(&mut self, name: String, value: LoxObject) {
         self.values.insert(name, value);
     }
+
+    pub fn bindings(&self) -> Vec<(String, LoxObject)> {
+        let mut bindings: Vec<(String, LoxObject)> = self.values
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone()))
+            .collect();
+        bindings.sort_by(|a, b| a.0.cmp(&b.0));
+        bindings
+    }
 }
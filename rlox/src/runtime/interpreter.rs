@@ -1,73 +1,77 @@
 use lox::*;
 use runtime::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::mem;
+use std::rc::Rc;
 use syntax::*;
 
+pub const MAX_CALL_DEPTH: usize = 1000;
+
 pub struct Interpreter {
-    environment: Environment,
-    error: Option<String>,
-    source: String,
+    environment: Rc<RefCell<Environment>>,
+    globals: Rc<RefCell<Environment>>,
+    locals: HashMap<usize, usize>,
+    error: Option<(ErrorCode, String, Position)>,
+    returning: Option<LoxObject>,
+    depth: usize,
     pub source_file: Option<String>
 }
 
 impl Interpreter {
     pub fn new() -> Interpreter {
+        let globals = Rc::new(RefCell::new(Environment::new()));
+
         Interpreter {
-            environment: Environment::new(),
+            environment: Rc::clone(&globals),
+            globals,
+            locals: HashMap::new(),
             error: None,
-            source: "".to_string(),
+            returning: None,
+            depth: 0,
             source_file: None
         }
     }
 
     pub fn interpret(
         &mut self,
-        stmts: Vec<Box<Stmt>>,
-        source: &str
-    ) -> Result<(), LoxError> {
-        self.source = source.to_string();
-
-        let mut iter = stmts.iter();
-        let mut last = None;
-
-        while self.error.is_none() {
-            match iter.next() {
-                None => {
-                    break;
-                },
-                Some(stmt) => {
-                    last = Some(stmt);
-                    self.visit_stmt(&stmt);
-                }
+        stmts: Vec<Box<Stmt>>
+    ) -> Result<Option<LoxObject>, Box<Diagnostic>> {
+        let mut value = None;
+
+        for stmt in stmts.iter() {
+            if self.is_unwinding() {
+                break;
             }
+            let result = self.visit_stmt(stmt);
+            value = match **stmt {
+                Stmt::Expr(_, _) => Some(result),
+                _ => None
+            };
         }
 
         match self.error {
-            Some(ref message) => {
-                let position = match last {
-                    Some(stmt) => match **stmt {
-                        Stmt::Expr(_, position) => position,
-                        Stmt::Print(_, position) => position,
-                        Stmt::Var(_, _, position) => position,
-                    },
-                    None => Position::new(0, 0)
-                };
-
-                Err(
-                    LoxError::new(
-                        LoxErrorTy::Runtime,
-                        &message,
-                        &self.source,
-                        &self.source_file,
-                        position
-                    )
+            Some((code, ref message, position)) => Err(Box::new(
+                Diagnostic::new(
+                    LoxErrorTy::Runtime,
+                    code,
+                    message,
+                    &self.source_file,
+                    position
                 )
-            },
-            None => {
-                Ok(())
-            }
+            )),
+            None => Ok(value)
         }
     }
 
+    pub fn globals(&self) -> Rc<RefCell<Environment>> {
+        Rc::clone(&self.globals)
+    }
+
+    pub fn resolve(&mut self, locals: HashMap<usize, usize>) {
+        self.locals.extend(locals);
+    }
+
     pub fn is_truthy(&mut self, o: &LoxObject) -> bool {
         match o {
             LoxObject::Boolean(v) => *v,
@@ -83,21 +87,264 @@ impl Interpreter {
             (LoxObject::Boolean(a), LoxObject::Boolean(b)) => a == b,
             (LoxObject::Number(a), LoxObject::Number(b)) => a == b,
             (LoxObject::String(a), LoxObject::String(b)) => a == b,
+            (LoxObject::Class(a), LoxObject::Class(b)) => Rc::ptr_eq(a, b),
+            (LoxObject::Closure(a), LoxObject::Closure(b)) => Rc::ptr_eq(a, b),
+            (LoxObject::Instance(a), LoxObject::Instance(b)) => Rc::ptr_eq(a, b),
             (_, _) => false
         }
     }
 
+    pub fn execute_block(
+        &mut self,
+        stmts: &[Box<Stmt>],
+        environment: Environment
+    ) {
+        let previous = mem::replace(
+            &mut self.environment,
+            Rc::new(RefCell::new(environment))
+        );
+
+        for stmt in stmts {
+            if self.is_unwinding() {
+                break;
+            }
+            self.visit_stmt(stmt);
+        }
+
+        self.environment = previous;
+    }
+
+    pub fn call(
+        &mut self,
+        callee: LoxObject,
+        arguments: Vec<LoxObject>,
+        position: Position
+    ) -> LoxObject {
+        let arity = match callee {
+            LoxObject::Class(ref class) => class.arity(),
+            LoxObject::Closure(ref function) => function.arity(),
+            _ => {
+                self.error(
+                    ErrorCode::NotCallable,
+                    "Can only call functions and classes.",
+                    position
+                );
+                return LoxObject::Nil;
+            }
+        };
+
+        if arguments.len() != arity {
+            self.error(
+                ErrorCode::ArityMismatch,
+                &format!(
+                    "Expected {} arguments but got {}.",
+                    arity,
+                    arguments.len()
+                ),
+                position
+            );
+            return LoxObject::Nil;
+        }
+
+        if self.depth >= MAX_CALL_DEPTH {
+            self.error(ErrorCode::StackOverflow, "Stack overflow.", position);
+            return LoxObject::Nil;
+        }
+
+        match callee {
+            LoxObject::Class(class) => {
+                let instance = LoxObject::Instance(
+                    Rc::new(RefCell::new(LoxInstance::new(Rc::clone(&class))))
+                );
+                if let Some(initializer) = class.find_method("init") {
+                    let initializer = initializer.bind(instance.clone());
+                    self.call_function(&initializer, arguments);
+                }
+                instance
+            },
+            LoxObject::Closure(function) => {
+                self.call_function(&function, arguments)
+            },
+            _ => LoxObject::Nil
+        }
+    }
+
+    pub fn call_function(
+        &mut self,
+        function: &LoxFunction,
+        arguments: Vec<LoxObject>
+    ) -> LoxObject {
+        let mut environment = Environment::new_enclosed(
+            Rc::clone(&function.closure)
+        );
+        for (param, argument) in function.params.iter().zip(arguments) {
+            environment.define(param.to_string(), argument);
+        }
+
+        self.depth += 1;
+        self.execute_block(&function.body, environment);
+        self.depth -= 1;
+
+        let value = self.returning.take().unwrap_or(LoxObject::Nil);
+
+        if function.is_initializer {
+            function.closure
+                    .borrow()
+                    .get_at(0, "this".to_string())
+                    .unwrap_or(LoxObject::Nil)
+        } else {
+            value
+        }
+    }
+
     pub fn reset(&mut self) {
+        self.environment = Rc::clone(&self.globals);
         self.error = None;
+        self.returning = None;
+        self.depth = 0;
+    }
+
+    fn number_operands(
+        &mut self,
+        operator: &Token,
+        lhs: &LoxObject,
+        rhs: &LoxObject
+    ) -> Option<(f64, f64)> {
+        match (lhs, rhs) {
+            (LoxObject::Number(a), LoxObject::Number(b)) => Some((*a, *b)),
+            (_, _) => {
+                self.error(
+                    ErrorCode::InvalidOperand,
+                    "Operands must be numbers.",
+                    operator.position
+                );
+                None
+            }
+        }
+    }
+
+    fn error(&mut self, code: ErrorCode, message: &str, position: Position) {
+        if self.error.is_none() {
+            self.error = Some((code, message.to_string(), position));
+        }
+    }
+
+    fn is_unwinding(&self) -> bool {
+        self.error.is_some() || self.returning.is_some()
+    }
+
+    fn look_up_variable(&self, name: &str, id: usize) -> Option<LoxObject> {
+        match self.locals.get(&id) {
+            Some(distance) => {
+                self.environment.borrow().get_at(*distance, name.to_string())
+            },
+            None => self.globals.borrow().get(name.to_string())
+        }
+    }
+
+    fn assign_variable(&self, name: &str, id: usize, value: LoxObject) -> bool {
+        match self.locals.get(&id) {
+            Some(distance) => {
+                self.environment
+                    .borrow_mut()
+                    .assign_at(*distance, name.to_string(), value)
+            },
+            None => self.globals.borrow_mut().assign(name.to_string(), value)
+        }
     }
 }
 
 impl Visitor<LoxObject> for Interpreter {
     fn visit_stmt(&mut self, s: &Stmt) -> LoxObject {
         match s {
+            Stmt::Block(stmts, _) => {
+                let environment = Environment::new_enclosed(
+                    Rc::clone(&self.environment)
+                );
+                self.execute_block(stmts, environment);
+                LoxObject::Nil
+            },
+            Stmt::Class(name, _, superclass, methods, _) => {
+                let superclass = match superclass {
+                    Some(expr) => match self.visit_expr(expr) {
+                        LoxObject::Class(class) => Some(class),
+                        _ => {
+                            let position = AstPosition::new().get_expr_position(expr);
+                            self.error(
+                                ErrorCode::InvalidSuperclass,
+                                "Superclass must be a class.",
+                                position
+                            );
+                            return LoxObject::Nil;
+                        }
+                    },
+                    None => None
+                };
+
+                let enclosing = Rc::clone(&self.environment);
+                if let Some(ref superclass) = superclass {
+                    let mut environment = Environment::new_enclosed(
+                        Rc::clone(&enclosing)
+                    );
+                    environment.define(
+                        "super".to_string(),
+                        LoxObject::Class(Rc::clone(superclass))
+                    );
+                    self.environment = Rc::new(RefCell::new(environment));
+                }
+
+                let mut functions = HashMap::new();
+
+                for method in methods {
+                    if let Stmt::Function(name, _, params, body, _) = &**method {
+                        let function = LoxFunction::new(
+                            name.to_string(),
+                            params.iter().map(|(param, _)| param.to_string()).collect(),
+                            Rc::clone(body),
+                            Rc::clone(&self.environment),
+                            name == "init"
+                        );
+                        functions.insert(name.to_string(), Rc::new(function));
+                    }
+                }
+
+                self.environment = enclosing;
+
+                let class = LoxClass::new(name.to_string(), superclass, functions);
+                self.environment.borrow_mut().define(
+                    name.to_string(),
+                    LoxObject::Class(Rc::new(class))
+                );
+                LoxObject::Nil
+            },
             Stmt::Expr(expression, _) => {
                 self.visit_expr(expression)
             },
+            Stmt::Function(name, _, params, body, _) => {
+                let function = LoxFunction::new(
+                    name.to_string(),
+                    params.iter().map(|(param, _)| param.to_string()).collect(),
+                    Rc::clone(body),
+                    Rc::clone(&self.environment),
+                    false
+                );
+                self.environment.borrow_mut().define(
+                    name.to_string(),
+                    LoxObject::Closure(Rc::new(function))
+                );
+                LoxObject::Nil
+            },
+            Stmt::If(condition, then_branch, else_branch, _) => {
+                let value = self.visit_expr(condition);
+                if self.error.is_none() {
+                    if self.is_truthy(&value) {
+                        self.visit_stmt(then_branch);
+                    } else if let Some(else_branch) = else_branch {
+                        self.visit_stmt(else_branch);
+                    }
+                }
+                LoxObject::Nil
+            },
             Stmt::Print(expression, _) => {
                 let value = self.visit_expr(expression);
                 if self.error.is_none() {
@@ -105,12 +352,34 @@ impl Visitor<LoxObject> for Interpreter {
                 }
                 LoxObject::Nil
             },
-            Stmt::Var(name, initializer, _) => {
+            Stmt::Return(value, _) => {
+                let value = match value {
+                    Some(expr) => self.visit_expr(expr),
+                    None => LoxObject::Nil
+                };
+                if self.error.is_none() {
+                    self.returning = Some(value);
+                }
+                LoxObject::Nil
+            },
+            Stmt::Var(name, _, initializer, _) => {
                 let value = match initializer {
                     Some(expr) => self.visit_expr(expr),
                     None => LoxObject::Nil
                 };
-                self.environment.define(name.to_string(), value);
+                if self.error.is_none() {
+                    self.environment.borrow_mut().define(name.to_string(), value);
+                }
+                LoxObject::Nil
+            },
+            Stmt::While(condition, body, _) => {
+                while !self.is_unwinding() {
+                    let value = self.visit_expr(condition);
+                    if self.error.is_some() || !self.is_truthy(&value) {
+                        break;
+                    }
+                    self.visit_stmt(body);
+                }
                 LoxObject::Nil
             }
         }
@@ -118,15 +387,149 @@ impl Visitor<LoxObject> for Interpreter {
 
     fn visit_expr(&mut self, e: &Expr) -> LoxObject {
         match e {
+            Expr::Assign(name, id, expression, position) => {
+                let value = self.visit_expr(expression);
+                if self.error.is_some() {
+                    return LoxObject::Nil;
+                }
+                if !self.assign_variable(name, *id, value.clone()) {
+                    self.error(
+                        ErrorCode::UndefinedVariable,
+                        &format!("Undefined variable `{}`.", name),
+                        *position
+                    );
+                }
+                value
+            },
+            Expr::Call(callee, arguments, _) => {
+                let position = AstPosition::new().get_expr_position(callee);
+                let callee = self.visit_expr(callee);
+                let mut values = Vec::new();
+                for argument in arguments {
+                    if self.error.is_some() {
+                        break;
+                    }
+                    values.push(self.visit_expr(argument));
+                }
+                if self.error.is_some() {
+                    return LoxObject::Nil;
+                }
+
+                self.call(callee, values, position)
+            },
+            Expr::Get(object, name, position) => {
+                match self.visit_expr(object) {
+                    LoxObject::Instance(instance) => {
+                        match LoxInstance::get(&instance, name) {
+                            Some(value) => value,
+                            None => {
+                                self.error(
+                                    ErrorCode::UndefinedProperty,
+                                    &format!("Undefined property `{}`.", name),
+                                    *position
+                                );
+                                LoxObject::Nil
+                            }
+                        }
+                    },
+                    _ => {
+                        let position = AstPosition::new().get_expr_position(object);
+                        self.error(
+                            ErrorCode::NotAnInstance,
+                            "Only instances have properties.",
+                            position
+                        );
+                        LoxObject::Nil
+                    }
+                }
+            },
             Expr::Grouping(expression, _) => {
                 self.visit_expr(expression)
             },
+            Expr::Interpolation(parts, _) => {
+                let mut value = String::new();
+                for part in parts {
+                    let part = self.visit_expr(part);
+                    if self.is_unwinding() {
+                        return LoxObject::Nil;
+                    }
+                    value.push_str(&part.to_string());
+                }
+                LoxObject::String(value)
+            },
             Expr::Literal(value, _) => match value {
                 Literal::Number(v) => LoxObject::Number(*v),
                 Literal::String(v) => LoxObject::String(v.to_string()),
                 Literal::Boolean(v) => LoxObject::Boolean(*v),
                 Literal::Nil => LoxObject::Nil
             },
+            Expr::Logical(left, operator, right, _) => {
+                let lhs = self.visit_expr(left);
+                if self.error.is_some() {
+                    return LoxObject::Nil;
+                }
+
+                match operator.ty {
+                    TokenTy::Or if self.is_truthy(&lhs) => lhs,
+                    TokenTy::And if !self.is_truthy(&lhs) => lhs,
+                    _ => self.visit_expr(right)
+                }
+            },
+            Expr::Set(object, name, value, _) => {
+                match self.visit_expr(object) {
+                    LoxObject::Instance(instance) => {
+                        let value = self.visit_expr(value);
+                        if self.error.is_some() {
+                            return LoxObject::Nil;
+                        }
+                        instance.borrow_mut().set(name.to_string(), value.clone());
+                        value
+                    },
+                    _ => {
+                        let position = AstPosition::new().get_expr_position(object);
+                        self.error(
+                            ErrorCode::NotAnInstance,
+                            "Only instances have fields.",
+                            position
+                        );
+                        LoxObject::Nil
+                    }
+                }
+            },
+            Expr::Super(id, method, position) => {
+                let distance = match self.locals.get(id) {
+                    Some(distance) => *distance,
+                    None => return LoxObject::Nil
+                };
+                let superclass = self.environment
+                                     .borrow()
+                                     .get_at(distance, "super".to_string());
+                let object = self.environment
+                                 .borrow()
+                                 .get_at(distance - 1, "this".to_string());
+
+                match (superclass, object) {
+                    (Some(LoxObject::Class(superclass)), Some(object)) => {
+                        match superclass.find_method(method) {
+                            Some(function) => {
+                                LoxObject::Closure(Rc::new(function.bind(object)))
+                            },
+                            None => {
+                                self.error(
+                                    ErrorCode::UndefinedProperty,
+                                    &format!("Undefined property `{}`.", method),
+                                    *position
+                                );
+                                LoxObject::Nil
+                            }
+                        }
+                    },
+                    _ => LoxObject::Nil
+                }
+            },
+            Expr::This(id, _) => {
+                self.look_up_variable("this", *id).unwrap_or(LoxObject::Nil)
+            },
             Expr::Unary(operator, right, _) => {
                 let object = self.visit_expr(right);
 
@@ -136,33 +539,40 @@ impl Visitor<LoxObject> for Interpreter {
                     },
                     TokenTy::Minus => match object {
                         LoxObject::Number(v) => LoxObject::Number(-v),
-                        _ => object
+                        _ => {
+                            self.error(
+                                ErrorCode::InvalidOperand,
+                                "Operand must be a number.",
+                                operator.position
+                            );
+                            LoxObject::Nil
+                        }
                     },
                     _ => object
                 }
             },
             Expr::Binary(left, operator, right, _) => {
                 let lhs = self.visit_expr(left);
+                if self.error.is_some() {
+                    return LoxObject::Nil;
+                }
                 let rhs = self.visit_expr(right);
 
                 match operator.ty {
-                    TokenTy::Minus => match (lhs, rhs) {
-                        (LoxObject::Number(a), LoxObject::Number(b)) => {
-                            LoxObject::Number(a + b)
-                        },
-                        (_, _) => LoxObject::Nil
+                    TokenTy::Minus => {
+                        self.number_operands(operator, &lhs, &rhs)
+                            .map(|(a, b)| LoxObject::Number(a - b))
+                            .unwrap_or(LoxObject::Nil)
                     },
-                    TokenTy::Slash => match (lhs, rhs) {
-                        (LoxObject::Number(a), LoxObject::Number(b)) => {
-                            LoxObject::Number(a / b)
-                        },
-                        (_, _) => LoxObject::Nil
+                    TokenTy::Slash => {
+                        self.number_operands(operator, &lhs, &rhs)
+                            .map(|(a, b)| LoxObject::Number(a / b))
+                            .unwrap_or(LoxObject::Nil)
                     },
-                    TokenTy::Star => match (lhs, rhs) {
-                        (LoxObject::Number(a), LoxObject::Number(b)) => {
-                            LoxObject::Number(a * b)
-                        },
-                        (_, _) => LoxObject::Nil
+                    TokenTy::Star => {
+                        self.number_operands(operator, &lhs, &rhs)
+                            .map(|(a, b)| LoxObject::Number(a * b))
+                            .unwrap_or(LoxObject::Nil)
                     },
                     TokenTy::Plus => match (lhs, rhs) {
                         (LoxObject::Number(a), LoxObject::Number(b)) => {
@@ -171,31 +581,34 @@ impl Visitor<LoxObject> for Interpreter {
                         (LoxObject::String(a), LoxObject::String(b)) => {
                             LoxObject::String([a, b].join(""))
                         },
-                        (_, _) => LoxObject::Nil
+                        (_, _) => {
+                            self.error(
+                                ErrorCode::InvalidOperand,
+                                "Operands must be two numbers or two strings.",
+                                operator.position
+                            );
+                            LoxObject::Nil
+                        }
                     },
-                    TokenTy::Greater => match (lhs, rhs) {
-                        (LoxObject::Boolean(a), LoxObject::Boolean(b)) => {
-                            LoxObject::Boolean(a > b)
-                        },
-                        (_, _) => LoxObject::Nil
+                    TokenTy::Greater => {
+                        self.number_operands(operator, &lhs, &rhs)
+                            .map(|(a, b)| LoxObject::Boolean(a > b))
+                            .unwrap_or(LoxObject::Nil)
                     },
-                    TokenTy::GreaterEqual => match (lhs, rhs) {
-                        (LoxObject::Boolean(a), LoxObject::Boolean(b)) => {
-                            LoxObject::Boolean(a >= b)
-                        },
-                        (_, _) => LoxObject::Nil
+                    TokenTy::GreaterEqual => {
+                        self.number_operands(operator, &lhs, &rhs)
+                            .map(|(a, b)| LoxObject::Boolean(a >= b))
+                            .unwrap_or(LoxObject::Nil)
                     },
-                    TokenTy::Less => match (lhs, rhs) {
-                        (LoxObject::Boolean(a), LoxObject::Boolean(b)) => {
-                            LoxObject::Boolean(a < b)
-                        },
-                        (_, _) => LoxObject::Nil
+                    TokenTy::Less => {
+                        self.number_operands(operator, &lhs, &rhs)
+                            .map(|(a, b)| LoxObject::Boolean(a < b))
+                            .unwrap_or(LoxObject::Nil)
                     },
-                    TokenTy::LessEqual => match (lhs, rhs) {
-                        (LoxObject::Boolean(a), LoxObject::Boolean(b)) => {
-                            LoxObject::Boolean(a <= b)
-                        },
-                        (_, _) => LoxObject::Nil
+                    TokenTy::LessEqual => {
+                        self.number_operands(operator, &lhs, &rhs)
+                            .map(|(a, b)| LoxObject::Boolean(a <= b))
+                            .unwrap_or(LoxObject::Nil)
                     },
                     TokenTy::BangEqual => {
                         LoxObject::Boolean(!self.is_equal(&lhs, &rhs))
@@ -206,16 +619,15 @@ impl Visitor<LoxObject> for Interpreter {
                     _ => LoxObject::Nil
                 }
             },
-            Expr::Variable(name, _) => {
-                match self.environment.get(name.to_string()) {
-                    Some(value) => {
-                        value.clone()
-                    },
+            Expr::Variable(name, id, position) => {
+                match self.look_up_variable(name, *id) {
+                    Some(value) => value,
                     None => {
-                        self.error = Some(format!(
-                            "Undefined variable `{}`.",
-                            name
-                        ));
+                        self.error(
+                            ErrorCode::UndefinedVariable,
+                            &format!("Undefined variable `{}`.", name),
+                            *position
+                        );
                         LoxObject::Nil
                     }
                 }
@@ -223,3 +635,334 @@ impl Visitor<LoxObject> for Interpreter {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    fn run(source: &str) -> Result<Option<LoxObject>, Box<Diagnostic>> {
+        let stmts = Parser::new(
+            Scanner::new(source).scan_tokens().expect(""),
+            source
+        ).parse().expect("");
+        let locals = Resolver::new().resolve(&stmts).expect("");
+        let mut interpreter = Interpreter::new();
+        interpreter.resolve(locals);
+
+        interpreter.interpret(stmts)
+    }
+
+    fn interpret(source: &str) -> Result<(), (String, Position)> {
+        run(source).map(|_| ())
+                   .map_err(|err| (err.message, err.position))
+    }
+
+    fn evaluate(source: &str) -> String {
+        run(source).expect("")
+                   .map(|value| value.to_string())
+                   .unwrap_or_default()
+    }
+
+    #[test]
+    fn it_shadows_variables_in_blocks() {
+        assert_eq!(
+            evaluate("
+                var a = \"global\";
+                var b = \"outer\";
+                { var a = \"block\"; b = a; }
+                a + \" \" + b;
+            "),
+            "global block"
+        );
+        assert_eq!(
+            interpret("{ var c = 1; }\nc;"),
+            Err(("Undefined variable `c`.".to_string(), Position::new(1, 15)))
+        );
+    }
+
+    #[test]
+    fn it_returns_the_deciding_operand() {
+        assert_eq!(evaluate("nil or \"yes\";"), "yes");
+        assert_eq!(evaluate("\"first\" or \"second\";"), "first");
+        assert_eq!(evaluate("1 and 2;"), "2");
+        assert_eq!(evaluate("false and 2;"), "false");
+        assert_eq!(
+            evaluate("var a = 1; true or (a = 2); nil and (a = 3); a;"),
+            "1"
+        );
+    }
+
+    #[test]
+    fn it_calls_functions() {
+        assert_eq!(
+            evaluate("
+                fun fib(n) {
+                    if (n < 2) return n;
+                    return fib(n - 1) + fib(n - 2);
+                }
+                fib(10);
+            "),
+            "55"
+        );
+        assert_eq!(evaluate("fun f() {} f();"), "nil");
+        assert_eq!(evaluate("fun f() {} f;"), "<fn f>");
+    }
+
+    #[test]
+    fn it_returns_out_of_nested_loops_and_blocks() {
+        assert_eq!(
+            evaluate("
+                fun find() {
+                    for (var i = 1; ; i = i + 1) {
+                        for (var j = 1; j <= i; j = j + 1) {
+                            if (i * j == 6) return i + j;
+                        }
+                    }
+                }
+                find();
+            "),
+            "5"
+        );
+        assert_eq!(
+            evaluate("
+                fun first() {
+                    var i = 0;
+                    while (true) {
+                        { { if (i == 3) return i * 10; } }
+                        i = i + 1;
+                    }
+                }
+                first();
+            "),
+            "30"
+        );
+    }
+
+    #[test]
+    fn it_checks_arity() {
+        assert_eq!(
+            interpret("fun f(a, b) {}\nf(1);"),
+            Err(("Expected 2 arguments but got 1.".to_string(), Position::new(1, 15)))
+        );
+        assert_eq!(
+            interpret("var f = 1;\nf();"),
+            Err(("Can only call functions and classes.".to_string(), Position::new(1, 11)))
+        );
+    }
+
+    #[test]
+    fn it_locates_runtime_errors_across_lines() {
+        let source = "fun f(n) { return n; }\nf(\n  1)();";
+        let position = run(source).expect_err("").position;
+
+        assert_eq!(position, Position::new(7, 23));
+        assert_eq!(
+            (position.line, position.column, position.end_line, position.end_column),
+            (1, 0, 2, 4)
+        );
+    }
+
+    #[test]
+    fn it_limits_call_depth() {
+        let overflow = thread::Builder::new()
+            .stack_size(64 * 1024 * 1024)
+            .spawn(|| interpret("fun f(n) {\n  return f(n + 1);\n}\nf(0);"))
+            .expect("")
+            .join()
+            .expect("");
+
+        assert_eq!(
+            overflow,
+            Err(("Stack overflow.".to_string(), Position::new(1, 20)))
+        );
+    }
+
+    #[test]
+    fn it_closes_over_the_declaring_environment() {
+        assert_eq!(
+            evaluate("
+                fun make_counter() {
+                    var i = 0;
+                    fun count() {
+                        i = i + 1;
+                        return i;
+                    }
+                    return count;
+                }
+                var a = make_counter();
+                var b = make_counter();
+                a();
+                a();
+                b();
+                a();
+            "),
+            "3"
+        );
+        assert_eq!(
+            evaluate("
+                var a = \"global\";
+                var seen;
+                {
+                    fun show() { return a; }
+                    seen = show();
+                    var a = \"block\";
+                    seen = seen + \" \" + show();
+                }
+                seen;
+            "),
+            "global global"
+        );
+    }
+
+    #[test]
+    fn it_returns_this_from_initializers() {
+        let class = "
+            class Point {
+                init(x) {
+                    this.x = x;
+                    if (x > 1) return;
+                    this.small = true;
+                }
+            }
+        ";
+
+        assert_eq!(evaluate(&format!("{} Point(1).x;", class)), "1");
+        assert_eq!(evaluate(&format!("{} Point(2);", class)), "<Point instance>");
+        assert_eq!(
+            evaluate(&format!("{} var p = Point(1); p.init(3) == p;", class)),
+            "true"
+        );
+        assert_eq!(
+            evaluate(&format!("{} var p = Point(1); p.init(3); p.x;", class)),
+            "3"
+        );
+        assert_eq!(evaluate(&format!("{} Point;", class)), "<class Point>");
+    }
+
+    #[test]
+    fn it_binds_methods_to_their_instance() {
+        assert_eq!(
+            evaluate("
+                class Counter {
+                    init() { this.count = 0; }
+                    increment() {
+                        this.count = this.count + 1;
+                        return this.count;
+                    }
+                }
+                var counter = Counter();
+                var increment = counter.increment;
+                increment();
+                increment();
+                counter.count;
+            "),
+            "2"
+        );
+        assert_eq!(
+            interpret("class A {}\nA().missing;"),
+            Err(("Undefined property `missing`.".to_string(), Position::new(7, 15)))
+        );
+    }
+
+    #[test]
+    fn it_dispatches_through_superclasses() {
+        assert_eq!(
+            evaluate("
+                class A {
+                    name() { return \"A\"; }
+                    greet() { return \"hi \" + this.name(); }
+                }
+                class B < A {
+                    name() { return \"B\"; }
+                    greet() { return super.greet() + \"!\"; }
+                }
+                class C < B {}
+                C().greet();
+            "),
+            "hi B!"
+        );
+        assert_eq!(
+            evaluate("
+                class A { method() { return \"A\"; } }
+                class B < A {
+                    method() { return \"B\"; }
+                    test() { return super.method(); }
+                }
+                class C < B {}
+                C().test();
+            "),
+            "A"
+        );
+        assert_eq!(
+            interpret("var N = 1;\nclass D < N {}"),
+            Err(("Superclass must be a class.".to_string(), Position::new(1, 21)))
+        );
+    }
+
+    #[test]
+    fn it_rejects_non_number_operands() {
+        assert_eq!(
+            interpret("\"a\" - 1;"),
+            Err(("Operands must be numbers.".to_string(), Position::new(1, 4)))
+        );
+        assert_eq!(
+            interpret("true > 1;"),
+            Err(("Operands must be numbers.".to_string(), Position::new(1, 5)))
+        );
+        assert_eq!(
+            interpret("nil <= nil;"),
+            Err(("Operands must be numbers.".to_string(), Position::new(2, 4)))
+        );
+        assert_eq!(interpret("1 - 2 * 3 / 4 > -5;"), Ok(()));
+    }
+
+    #[test]
+    fn it_rejects_non_number_operand() {
+        assert_eq!(
+            interpret("-\"x\";"),
+            Err(("Operand must be a number.".to_string(), Position::new(1, 0)))
+        );
+    }
+
+    #[test]
+    fn it_interpolates_strings() {
+        let check = |expected: &str| format!(
+            "class A {{}} var n = 2;
+            var s = \"${{n}} + ${{nil}} = ${{n + 1 == 3}} ${{A}} ${{A()}} ${{\"x\"}}\";
+            if (s != \"{}\") -s;",
+            expected
+        );
+
+        assert_eq!(
+            interpret(&check("2 + nil = true <class A> <A instance> x")),
+            Ok(())
+        );
+        assert!(interpret(&check("2")).is_err());
+        assert_eq!(
+            interpret("print \"${-nil}\";"),
+            Err(("Operand must be a number.".to_string(), Position::new(1, 9)))
+        );
+    }
+
+    #[test]
+    fn it_reports_undefined_assignment_targets() {
+        assert_eq!(
+            interpret("var x;\ny = 3;"),
+            Err(("Undefined variable `y`.".to_string(), Position::new(1, 7)))
+        );
+        assert_eq!(interpret("var x; { x = 3; }"), Ok(()));
+    }
+
+    #[test]
+    fn it_rejects_mixed_addition() {
+        assert_eq!(
+            interpret("var a = 1;\nvar b = a + \"1\";"),
+            Err((
+                "Operands must be two numbers or two strings.".to_string(),
+                Position::new(1, 21)
+            ))
+        );
+        assert_eq!(interpret("\"a\" + \"b\"; 1 + 2;"), Ok(()));
+    }
+}
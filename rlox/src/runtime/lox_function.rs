@@ -0,0 +1,59 @@
+use runtime::*;
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+use syntax::*;
+
+pub struct LoxFunction {
+    pub name: String,
+    pub params: Vec<String>,
+    pub body: Rc<Vec<Box<Stmt>>>,
+    pub closure: Rc<RefCell<Environment>>,
+    pub is_initializer: bool
+}
+
+impl LoxFunction {
+    pub fn new(
+        name: String,
+        params: Vec<String>,
+        body: Rc<Vec<Box<Stmt>>>,
+        closure: Rc<RefCell<Environment>>,
+        is_initializer: bool
+    ) -> LoxFunction {
+        LoxFunction { name, params, body, closure, is_initializer }
+    }
+
+    pub fn bind(&self, instance: LoxObject) -> LoxFunction {
+        let mut environment = Environment::new_enclosed(
+            Rc::clone(&self.closure)
+        );
+        environment.define("this".to_string(), instance);
+
+        LoxFunction::new(
+            self.name.to_string(),
+            self.params.clone(),
+            Rc::clone(&self.body),
+            Rc::new(RefCell::new(environment)),
+            self.is_initializer
+        )
+    }
+
+    pub fn arity(&self) -> usize {
+        self.params.len()
+    }
+}
+
+impl fmt::Debug for LoxFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LoxFunction")
+         .field("name", &self.name)
+         .field("params", &self.params)
+         .finish()
+    }
+}
+
+impl fmt::Display for LoxFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<fn {}>", self.name)
+    }
+}
@@ -0,0 +1,49 @@
+use runtime::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+pub struct LoxInstance {
+    pub class: Rc<LoxClass>,
+    fields: HashMap<String, LoxObject>
+}
+
+impl LoxInstance {
+    pub fn new(class: Rc<LoxClass>) -> LoxInstance {
+        LoxInstance {
+            class,
+            fields: HashMap::new()
+        }
+    }
+
+    pub fn get(instance: &Rc<RefCell<LoxInstance>>, name: &str) -> Option<LoxObject> {
+        if let Some(value) = instance.borrow().fields.get(name) {
+            return Some(value.clone());
+        }
+
+        let method = instance.borrow().class.find_method(name);
+        method.map(|method| {
+            let this = LoxObject::Instance(Rc::clone(instance));
+            LoxObject::Closure(Rc::new(method.bind(this)))
+        })
+    }
+
+    pub fn set(&mut self, name: String, value: LoxObject) {
+        self.fields.insert(name, value);
+    }
+}
+
+impl fmt::Debug for LoxInstance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LoxInstance")
+         .field("class", &self.class.name)
+         .finish()
+    }
+}
+
+impl fmt::Display for LoxInstance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<{} instance>", self.class.name)
+    }
+}
@@ -1,17 +1,40 @@
+use runtime::*;
+use std::cell::RefCell;
 use std::fmt;
+use std::rc::Rc;
 
 #[derive(Clone, Debug)]
 pub enum LoxObject {
     Boolean(bool),
+    Class(Rc<LoxClass>),
+    Closure(Rc<LoxFunction>),
+    Instance(Rc<RefCell<LoxInstance>>),
     Nil,
     Number(f64),
     String(String)
 }
 
+impl LoxObject {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            LoxObject::Boolean(_) => "boolean",
+            LoxObject::Class(_) => "class",
+            LoxObject::Closure(_) => "function",
+            LoxObject::Instance(_) => "instance",
+            LoxObject::Nil => "nil",
+            LoxObject::Number(_) => "number",
+            LoxObject::String(_) => "string"
+        }
+    }
+}
+
 impl fmt::Display for LoxObject {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             LoxObject::Boolean(v) => write!(f, "{}", v),
+            LoxObject::Class(v) => write!(f, "{}", v),
+            LoxObject::Closure(v) => write!(f, "{}", v),
+            LoxObject::Instance(v) => write!(f, "{}", v.borrow()),
             LoxObject::Number(v) => write!(f, "{}", v),
             LoxObject::Nil => write!(f, "nil"),
             LoxObject::String(v) => write!(f, "{}", v)
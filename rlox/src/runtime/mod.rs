@@ -1,7 +1,13 @@
 pub mod environment;
 pub mod interpreter;
+pub mod lox_class;
+pub mod lox_function;
+pub mod lox_instance;
 pub mod lox_object;
 
 pub use self::environment::*;
 pub use self::interpreter::*;
+pub use self::lox_class::*;
+pub use self::lox_function::*;
+pub use self::lox_instance::*;
 pub use self::lox_object::*;
@@ -19,54 +19,95 @@ impl AstPosition {
 impl Visitor<Position> for AstPosition {
     fn visit_stmt(&mut self, s: &Stmt) -> Position {
         match s {
+            Stmt::Block(stmts, position) => {
+                stmts.iter().fold(*position, |span, stmt| {
+                    span.join(self.visit_stmt(stmt))
+                })
+            },
+            Stmt::Class(_, _, superclass, methods, position) => {
+                let span = match superclass {
+                    Some(expr) => position.join(self.visit_expr(expr)),
+                    None => *position
+                };
+                methods.iter().fold(span, |span, method| {
+                    span.join(self.visit_stmt(method))
+                })
+            },
             Stmt::Expr(expression, position) => {
-                Position::new(
-                    position.length + self.visit_expr(expression).length,
-                    position.offset
-                )
+                position.join(self.visit_expr(expression))
+            },
+            Stmt::Function(_, _, _, body, position) => {
+                body.iter().fold(*position, |span, stmt| {
+                    span.join(self.visit_stmt(stmt))
+                })
+            },
+            Stmt::If(condition, then_branch, else_branch, position) => {
+                let span = position.join(self.visit_expr(condition))
+                                   .join(self.visit_stmt(then_branch));
+                match else_branch {
+                    Some(stmt) => span.join(self.visit_stmt(stmt)),
+                    None => span
+                }
             },
             Stmt::Print(expression, position) => {
-                Position::new(
-                    position.length + self.visit_expr(expression).length,
-                    position.offset
-                )
+                position.join(self.visit_expr(expression))
+            },
+            Stmt::Return(value, position) => {
+                match value {
+                    Some(expr) => position.join(self.visit_expr(expr)),
+                    None => *position
+                }
             },
-            Stmt::Var(_name, initializer, position) => {
+            Stmt::Var(_, _, initializer, position) => {
                 match initializer {
-                    Some(expr) => Position::new(
-                        position.length + self.visit_expr(expr).length,
-                        position.offset
-                    ),
-                    None => position.clone()
+                    Some(expr) => position.join(self.visit_expr(expr)),
+                    None => *position
                 }
+            },
+            Stmt::While(condition, body, position) => {
+                position.join(self.visit_expr(condition))
+                        .join(self.visit_stmt(body))
             }
         }
     }
 
     fn visit_expr(&mut self, e: &Expr) -> Position {
         match e {
+            Expr::Assign(_, _, value, position) => {
+                position.join(self.visit_expr(value))
+            },
             Expr::Binary(left, _, right, position) => {
-                Position::new(
-                    position.length +
-                    self.visit_expr(left).length +
-                    self.visit_expr(right).length,
-                    position.offset
+                position.join(self.visit_expr(left))
+                        .join(self.visit_expr(right))
+            },
+            Expr::Call(callee, arguments, position) => {
+                arguments.iter().fold(
+                    position.join(self.visit_expr(callee)),
+                    |span, argument| span.join(self.visit_expr(argument))
                 )
             },
+            Expr::Get(object, _, position) => {
+                position.join(self.visit_expr(object))
+            },
             Expr::Grouping(expression, position) => {
-                Position::new(
-                    position.length + self.visit_expr(expression).length,
-                    position.offset
-                )
+                position.join(self.visit_expr(expression))
+            },
+            Expr::Logical(left, _, right, position) => {
+                position.join(self.visit_expr(left))
+                        .join(self.visit_expr(right))
             },
+            Expr::Set(object, _, value, position) => {
+                position.join(self.visit_expr(object))
+                        .join(self.visit_expr(value))
+            },
+            Expr::Interpolation(_, position) => *position,
+            Expr::Super(_, _, position) => *position,
+            Expr::This(_, position) => *position,
             Expr::Unary(_, right, position) => {
-                Position::new(
-                    position.length + self.visit_expr(right).length,
-                    position.offset
-                )
+                position.join(self.visit_expr(right))
             },
-            Expr::Variable(_, position) => position.clone(),
-            Expr::Literal(_, position) => position.clone()
+            Expr::Variable(_, _, position) => *position,
+            Expr::Literal(_, position) => *position
         }
     }
 }
@@ -19,12 +19,71 @@ impl AstPrinter {
 impl Visitor<String> for AstPrinter {
     fn visit_stmt(&mut self, s: &Stmt) -> String {
         match s {
+            Stmt::Block(stmts, _) => {
+                let mut block = String::from("(block");
+                for stmt in stmts {
+                    block.push(' ');
+                    block.push_str(&self.visit_stmt(stmt));
+                }
+                block.push(')');
+                block
+            },
+            Stmt::Class(name, _, superclass, methods, _) => {
+                let mut class = match superclass {
+                    Some(superclass) => format!(
+                        "(class {} < {}",
+                        name,
+                        self.visit_expr(superclass)
+                    ),
+                    None => format!("(class {}", name)
+                };
+                for method in methods {
+                    class.push(' ');
+                    class.push_str(&self.visit_stmt(method));
+                }
+                class.push(')');
+                class
+            },
             Stmt::Expr(expression, _) => self.visit_expr(expression),
+            Stmt::Function(name, _, params, body, _) => {
+                let params: Vec<&str> = params.iter()
+                                              .map(|(param, _)| param.as_str())
+                                              .collect();
+                let mut function = format!("(fun {} ({})", name, params.join(" "));
+                for stmt in body.iter() {
+                    function.push(' ');
+                    function.push_str(&self.visit_stmt(stmt));
+                }
+                function.push(')');
+                function
+            },
+            Stmt::If(condition, then_branch, else_branch, _) => {
+                match else_branch {
+                    Some(else_branch) => format!(
+                        "(if {} {} {})",
+                        self.visit_expr(condition),
+                        self.visit_stmt(then_branch),
+                        self.visit_stmt(else_branch)
+                    ),
+                    None => format!(
+                        "(if {} {})",
+                        self.visit_expr(condition),
+                        self.visit_stmt(then_branch)
+                    )
+                }
+            },
             Stmt::Print(expression, _) => format!(
                 "(print {})",
                 self.visit_expr(expression)
             ),
-            Stmt::Var(name, initializer, _) => match initializer {
+            Stmt::Return(value, _) => match value {
+                Some(expr) => format!(
+                    "(return {})",
+                    self.visit_expr(expr)
+                ),
+                None => String::from("(return)")
+            },
+            Stmt::Var(name, _, initializer, _) => match initializer {
                 Some(expr) => format!(
                     "(var {} {})",
                     name,
@@ -34,18 +93,51 @@ impl Visitor<String> for AstPrinter {
                     "(var {})",
                     name
                 )
-            }
+            },
+            Stmt::While(condition, body, _) => format!(
+                "(while {} {})",
+                self.visit_expr(condition),
+                self.visit_stmt(body)
+            )
         }
     }
 
     fn visit_expr(&mut self, e: &Expr) -> String {
         match e {
+            Expr::Assign(name, _, value, _) => format!(
+                "(= {} {})",
+                name,
+                self.visit_expr(value)
+            ),
             Expr::Binary(left, operator, right, _) => format!(
                 "({} {} {})",
-                operator.to_string(),
+                operator,
                 self.visit_expr(left),
                 self.visit_expr(right)
             ),
+            Expr::Call(callee, arguments, _) => {
+                let mut call = format!("(call {}", self.visit_expr(callee));
+                for argument in arguments {
+                    call.push(' ');
+                    call.push_str(&self.visit_expr(argument));
+                }
+                call.push(')');
+                call
+            },
+            Expr::Interpolation(parts, _) => {
+                let mut interpolation = String::from("(interpolate");
+                for part in parts {
+                    interpolation.push(' ');
+                    interpolation.push_str(&self.visit_expr(part));
+                }
+                interpolation.push(')');
+                interpolation
+            },
+            Expr::Get(object, name, _) => format!(
+                "(. {} {})",
+                self.visit_expr(object),
+                name
+            ),
             Expr::Grouping(expression, _) => format!(
                 "({})",
                 self.visit_expr(expression)
@@ -56,15 +148,101 @@ impl Visitor<String> for AstPrinter {
                 Literal::Boolean(v) => format!("{}", v),
                 Literal::Nil => String::from("nil")
             },
+            Expr::Logical(left, operator, right, _) => format!(
+                "({} {} {})",
+                operator,
+                self.visit_expr(left),
+                self.visit_expr(right)
+            ),
+            Expr::Set(object, name, value, _) => format!(
+                "(= (. {} {}) {})",
+                self.visit_expr(object),
+                name,
+                self.visit_expr(value)
+            ),
+            Expr::Super(_, method, _) => format!("(. super {})", method),
+            Expr::This(_, _) => String::from("this"),
             Expr::Unary(operator, right, _) => format!(
                 "({} {})",
-                operator.to_string(),
+                operator,
                 self.visit_expr(right)
             ),
-            Expr::Variable(name, _) => format!(
-                "{}",
-                 name
-            )
+            Expr::Variable(name, _, _) => name.to_string()
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn print(source: &str) -> String {
+        let mut parser = Parser::new(
+            Scanner::new(source).scan_tokens().expect(""),
+            source
+        );
+        let stmts = parser.parse().expect("");
+
+        AstPrinter::new().visit_stmt(&stmts[0])
+    }
+
+    #[test]
+    fn it_prints_blocks() {
+        assert_eq!(
+            print("{ var a = 1; { print a; } }"),
+            "(block (var a 1) (block (print a)))"
+        );
+    }
+
+    #[test]
+    fn it_prints_logical_operators() {
+        assert_eq!(
+            print("a or b and c;"),
+            "(or a (and b c))"
+        );
+    }
+
+    #[test]
+    fn it_prints_interpolations() {
+        assert_eq!(
+            print("print \"a ${b} c ${d + 1}\";"),
+            "(print (interpolate \"a \" b \" c \" (+ d 1)))"
+        );
+    }
+
+    #[test]
+    fn it_prints_functions() {
+        assert_eq!(
+            print("fun add(a, b) { return a + b; }"),
+            "(fun add (a b) (return (+ a b)))"
+        );
+        assert_eq!(
+            print("f(1)(2, 3);"),
+            "(call (call f 1) 2 3)"
+        );
+    }
+
+    #[test]
+    fn it_prints_classes() {
+        assert_eq!(
+            print("class A { get() { return this.a; } set(v) { this.a = v; } }"),
+            "(class A (fun get () (return (. this a))) (fun set (v) (= (. this a) v)))"
+        );
+    }
+
+    #[test]
+    fn it_prints_inheritance() {
+        assert_eq!(
+            print("class B < A { f() { return super.f(); } }"),
+            "(class B < A (fun f () (return (call (. super f)))))"
+        );
+    }
+
+    #[test]
+    fn it_prints_desugared_for_loops() {
+        assert_eq!(
+            print("for (var i = 0; ; i = i + 1) print i;"),
+            "(block (var i 0) (while true (block (print i) (= i (+ i 1)))))"
+        );
+    }
+}
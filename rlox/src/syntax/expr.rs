@@ -1,10 +1,27 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
 use syntax::*;
 
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
 #[derive(Debug)]
 pub enum Expr {
+    Assign(String, usize, Box<Expr>, Position),
     Binary(Box<Expr>, Token, Box<Expr>, Position),
+    Call(Box<Expr>, Vec<Box<Expr>>, Position),
+    Get(Box<Expr>, String, Position),
     Grouping(Box<Expr>, Position),
+    Interpolation(Vec<Box<Expr>>, Position),
     Unary(Token, Box<Expr>, Position),
     Literal(Literal, Position),
-    Variable(String, Position)
+    Logical(Box<Expr>, Token, Box<Expr>, Position),
+    Set(Box<Expr>, String, Box<Expr>, Position),
+    Super(usize, String, Position),
+    This(usize, Position),
+    Variable(String, usize, Position)
+}
+
+impl Expr {
+    pub fn next_id() -> usize {
+        NEXT_ID.fetch_add(1, Ordering::Relaxed)
+    }
 }
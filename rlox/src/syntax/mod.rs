@@ -4,7 +4,9 @@ pub mod expr;
 pub mod literal;
 pub mod parser;
 pub mod position;
+pub mod resolver;
 pub mod scanner;
+pub mod source_map;
 pub mod stmt;
 pub mod token;
 pub mod token_ty;
@@ -16,7 +18,9 @@ pub use self::expr::*;
 pub use self::literal::*;
 pub use self::parser::*;
 pub use self::position::*;
+pub use self::resolver::*;
 pub use self::scanner::*;
+pub use self::source_map::*;
 pub use self::stmt::*;
 pub use self::token::*;
 pub use self::token_ty::*;
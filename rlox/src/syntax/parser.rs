@@ -1,67 +1,185 @@
 use lox::*;
 use std::mem::discriminant;
+use std::rc::Rc;
 use syntax::*;
 
+const MAX_ARGUMENTS: usize = 255;
+
 pub struct Parser {
     tokens: Vec<Token>,
-    source: String,
+    source_map: SourceMap,
     pub source_file: Option<String>,
+    pub repl: bool,
     curr: usize,
-    error: Option<String>
+    errors: Vec<(ErrorCode, String, Position)>,
+    panicking: bool
 }
 
 impl Parser {
     pub fn new(tokens: Vec<Token>, source: &str) -> Parser {
         Parser {
             tokens,
-            source: String::from(source),
+            source_map: SourceMap::new(source),
             source_file: None,
+            repl: false,
             curr: 0,
-            error: None
+            errors: Vec::new(),
+            panicking: false
         }
     }
 
-    pub fn parse(&mut self) -> Result<Vec<Box<Stmt>>, LoxError> {
+    pub fn parse(&mut self) -> Result<Vec<Box<Stmt>>, Vec<Diagnostic>> {
         let mut statements = Vec::new();
 
         while !self.is_at_end() {
             statements.push(self.declaration());
         }
 
-        let previous = self.previous();
+        if self.errors.is_empty() {
+            return Ok(statements);
+        }
 
-        match self.error {
-            Some(ref message) => Err(
-                LoxError::new(
+        Err(
+            self.errors.iter().map(|(code, message, position)| {
+                Diagnostic::new(
                     LoxErrorTy::Syntax,
-                    &message,
-                    &self.source,
+                    *code,
+                    message,
                     &self.source_file,
-                    previous.position
+                    *position
                 )
-            ),
-            None => Ok(statements)
-        }
+            }).collect()
+        )
     }
 
     fn declaration(&mut self) -> Box<Stmt> {
-        if self.match_1(TokenTy::Var) {
+        let stmt = if self.match_1(TokenTy::Class) {
+            self.class_declaration()
+        } else if self.match_1(TokenTy::Fun) {
+            self.function("function")
+        } else if self.match_1(TokenTy::Var) {
             self.var_declaration()
         } else {
             self.statement()
+        };
+
+        if self.panicking {
+            self.synchronize();
         }
+
+        stmt
     }
 
-    fn var_declaration(&mut self) -> Box<Stmt> {
-        let token = self.consume(
-            TokenTy::Identifier("".to_string()),
-            "Expected variable name."
-        );
-        let value = match token.ty {
-            TokenTy::Identifier(v) => v,
-            _ => panic!()
+    fn synchronize(&mut self) {
+        self.panicking = false;
+        self.advance();
+
+        while !self.is_at_end() {
+            if self.previous().ty == TokenTy::Semicolon {
+                return;
+            }
+
+            match self.peek().ty {
+                TokenTy::Class |
+                TokenTy::Fun |
+                TokenTy::Var |
+                TokenTy::For |
+                TokenTy::If |
+                TokenTy::While |
+                TokenTy::Print |
+                TokenTy::Return => return,
+                _ => {}
+            }
+
+            self.advance();
+        }
+    }
+
+    fn class_declaration(&mut self) -> Box<Stmt> {
+        let name = self.consume_identifier("Expected class name.");
+        let name_position = self.position();
+
+        let superclass = if self.match_1(TokenTy::Less) {
+            let superclass = self.consume_identifier("Expected superclass name.");
+            Some(Box::new(
+                Expr::Variable(superclass, Expr::next_id(), self.position())
+            ))
+        } else {
+            None
         };
 
+        self.consume(TokenTy::LeftBrace, "Expected `{` before class body.");
+
+        let mut methods = Vec::new();
+
+        while !self.check(&TokenTy::RightBrace) && !self.is_at_end() {
+            methods.push(self.function("method"));
+        }
+        self.consume(TokenTy::RightBrace, "Expected `}` after class body.");
+
+        Box::new(Stmt::Class(
+            name,
+            name_position,
+            superclass,
+            methods,
+            self.position()
+        ))
+    }
+
+    fn function(&mut self, kind: &str) -> Box<Stmt> {
+        let name = self.consume_identifier(
+            &format!("Expected {} name.", kind)
+        );
+        let name_position = self.position();
+        self.consume(
+            TokenTy::LeftParen,
+            &format!("Expected `(` after {} name.", kind)
+        );
+
+        let mut params = Vec::new();
+
+        if !self.check(&TokenTy::RightParen) {
+            loop {
+                if params.len() >= MAX_ARGUMENTS {
+                    let position = self.peek().position;
+                    self.error(
+                        ErrorCode::TooManyArguments,
+                        &format!(
+                            "Cannot have more than {} parameters.",
+                            MAX_ARGUMENTS
+                        ),
+                        position
+                    );
+                }
+                let param = self.consume_identifier("Expected parameter name.");
+                params.push((param, self.position()));
+
+                if !self.match_1(TokenTy::Comma) {
+                    break;
+                }
+            }
+        }
+        self.consume(TokenTy::RightParen, "Expected `)` after parameters.");
+        self.consume(
+            TokenTy::LeftBrace,
+            &format!("Expected `{{` before {} body.", kind)
+        );
+
+        let body = self.block();
+
+        Box::new(Stmt::Function(
+            name,
+            name_position,
+            params,
+            Rc::new(body),
+            self.position()
+        ))
+    }
+
+    fn var_declaration(&mut self) -> Box<Stmt> {
+        let name = self.consume_identifier("Expected variable name.");
+        let name_position = self.position();
+
         let mut initializer = None;
 
         if self.match_1(TokenTy::Equal) {
@@ -72,17 +190,106 @@ impl Parser {
             "Expected `;` after variable declaration."
         );
 
-        Box::new(Stmt::Var(value, initializer, self.position()))
+        Box::new(Stmt::Var(name, name_position, initializer, self.position()))
     }
 
     fn statement(&mut self) -> Box<Stmt> {
-        if self.match_1(TokenTy::Print) {
+        if self.match_1(TokenTy::For) {
+            self.for_statement()
+        } else if self.match_1(TokenTy::If) {
+            self.if_statement()
+        } else if self.match_1(TokenTy::Print) {
             self.print_statement()
+        } else if self.match_1(TokenTy::Return) {
+            self.return_statement()
+        } else if self.match_1(TokenTy::While) {
+            self.while_statement()
+        } else if self.match_1(TokenTy::LeftBrace) {
+            let stmts = self.block();
+            Box::new(Stmt::Block(stmts, self.position()))
         } else {
             self.expression_statement()
         }
     }
 
+    fn block(&mut self) -> Vec<Box<Stmt>> {
+        let mut statements = Vec::new();
+
+        while !self.check(&TokenTy::RightBrace) && !self.is_at_end() {
+            statements.push(self.declaration());
+        }
+        self.consume(
+            TokenTy::RightBrace,
+            "Expected `}` after block."
+        );
+
+        statements
+    }
+
+    fn for_statement(&mut self) -> Box<Stmt> {
+        self.consume(TokenTy::LeftParen, "Expected `(` after `for`.");
+
+        let initializer = if self.match_1(TokenTy::Semicolon) {
+            None
+        } else if self.match_1(TokenTy::Var) {
+            Some(self.var_declaration())
+        } else {
+            Some(self.expression_statement())
+        };
+
+        let condition = if self.check(&TokenTy::Semicolon) {
+            Box::new(Expr::Literal(Literal::Boolean(true), self.position()))
+        } else {
+            self.expression()
+        };
+        self.consume(
+            TokenTy::Semicolon,
+            "Expected `;` after loop condition."
+        );
+
+        let increment = if self.check(&TokenTy::RightParen) {
+            None
+        } else {
+            Some(self.expression())
+        };
+        self.consume(TokenTy::RightParen, "Expected `)` after for clauses.");
+
+        let mut body = self.statement();
+
+        if let Some(expr) = increment {
+            let position = self.position();
+            body = Box::new(Stmt::Block(
+                vec![body, Box::new(Stmt::Expr(expr, position))],
+                position
+            ));
+        }
+
+        body = Box::new(Stmt::While(condition, body, self.position()));
+
+        if let Some(stmt) = initializer {
+            body = Box::new(Stmt::Block(vec![stmt, body], self.position()));
+        }
+
+        body
+    }
+
+    fn if_statement(&mut self) -> Box<Stmt> {
+        self.consume(TokenTy::LeftParen, "Expected `(` after `if`.");
+        let condition = self.expression();
+        self.consume(TokenTy::RightParen, "Expected `)` after if condition.");
+
+        let then_branch = self.statement();
+        let else_branch = if self.match_1(TokenTy::Else) {
+            Some(self.statement())
+        } else {
+            None
+        };
+
+        Box::new(
+            Stmt::If(condition, then_branch, else_branch, self.position())
+        )
+    }
+
     fn print_statement(&mut self) -> Box<Stmt> {
         let expr = self.expression();
         self.consume(
@@ -94,15 +301,109 @@ impl Parser {
 
     fn expression_statement(&mut self) -> Box<Stmt> {
         let expr = self.expression();
-        self.consume(
-            TokenTy::Semicolon,
-            "Expected `;` after expression."
-        );
+        if !(self.repl && self.peek().ty == TokenTy::Eof) {
+            self.consume(
+                TokenTy::Semicolon,
+                "Expected `;` after expression."
+            );
+        }
         Box::new(Stmt::Expr(expr, self.position()))
     }
 
+    fn return_statement(&mut self) -> Box<Stmt> {
+        let keyword = self.position();
+        let value = if self.check(&TokenTy::Semicolon) {
+            None
+        } else {
+            Some(self.expression())
+        };
+        self.consume(TokenTy::Semicolon, "Expected `;` after return value.");
+
+        Box::new(Stmt::Return(value, keyword))
+    }
+
+    fn while_statement(&mut self) -> Box<Stmt> {
+        self.consume(TokenTy::LeftParen, "Expected `(` after `while`.");
+        let condition = self.expression();
+        self.consume(TokenTy::RightParen, "Expected `)` after condition.");
+        let body = self.statement();
+
+        Box::new(Stmt::While(condition, body, self.position()))
+    }
+
     fn expression(&mut self) -> Box<Expr> {
-        self.equality()
+        self.assignment()
+    }
+
+    fn assignment(&mut self) -> Box<Expr> {
+        let start = self.peek().position;
+        let expr = self.or();
+
+        if self.match_1(TokenTy::Equal) {
+            let equals = self.previous();
+            let value = self.assignment();
+
+            match *expr {
+                Expr::Variable(name, _, position) => {
+                    return Box::new(Expr::Assign(
+                        name,
+                        Expr::next_id(),
+                        value,
+                        position
+                    ));
+                },
+                Expr::Get(object, name, _) => {
+                    return Box::new(
+                        Expr::Set(object, name, value, self.position())
+                    );
+                },
+                _ => {}
+            }
+
+            let length = self.source_map.source()
+                                        .get(start.offset..equals.position.offset)
+                                        .unwrap_or("")
+                                        .trim_end()
+                                        .len();
+            let position = self.source_map.position(length, start.offset);
+            self.error(
+                ErrorCode::InvalidAssignmentTarget,
+                "Invalid assignment target.",
+                position
+            );
+        }
+
+        expr
+    }
+
+    fn or(&mut self) -> Box<Expr> {
+        let mut expr = self.and();
+
+        while self.match_1(TokenTy::Or) {
+            let operator = self.previous();
+            let right = self.and();
+
+            expr = Box::new(
+                Expr::Logical(expr, operator, right, self.position())
+            );
+        }
+
+        expr
+    }
+
+    fn and(&mut self) -> Box<Expr> {
+        let mut expr = self.equality();
+
+        while self.match_1(TokenTy::And) {
+            let operator = self.previous();
+            let right = self.equality();
+
+            expr = Box::new(
+                Expr::Logical(expr, operator, right, self.position())
+            );
+        }
+
+        expr
     }
 
     fn equality(&mut self) -> Box<Expr> {
@@ -179,10 +480,57 @@ impl Parser {
                 Expr::Unary(operator, right, self.position())
             )
         } else {
-            self.primary()
+            self.call()
         }
     }
 
+    fn call(&mut self) -> Box<Expr> {
+        let mut expr = self.primary();
+
+        loop {
+            if self.match_1(TokenTy::LeftParen) {
+                expr = self.finish_call(expr);
+            } else if self.match_1(TokenTy::Dot) {
+                let name = self.consume_identifier(
+                    "Expected property name after `.`."
+                );
+                expr = Box::new(Expr::Get(expr, name, self.position()));
+            } else {
+                break;
+            }
+        }
+
+        expr
+    }
+
+    fn finish_call(&mut self, callee: Box<Expr>) -> Box<Expr> {
+        let mut arguments = Vec::new();
+
+        if !self.check(&TokenTy::RightParen) {
+            loop {
+                if arguments.len() >= MAX_ARGUMENTS {
+                    let position = self.peek().position;
+                    self.error(
+                        ErrorCode::TooManyArguments,
+                        &format!(
+                            "Cannot have more than {} arguments.",
+                            MAX_ARGUMENTS
+                        ),
+                        position
+                    );
+                }
+                arguments.push(self.expression());
+
+                if !self.match_1(TokenTy::Comma) {
+                    break;
+                }
+            }
+        }
+        self.consume(TokenTy::RightParen, "Expected `)` after arguments.");
+
+        Box::new(Expr::Call(callee, arguments, self.position()))
+    }
+
     fn primary(&mut self) -> Box<Expr> {
         if self.match_1(TokenTy::False) {
             Box::new(Expr::Literal(Literal::Boolean(false), self.position()))
@@ -193,6 +541,16 @@ impl Parser {
         else if self.match_1(TokenTy::Nil) {
             Box::new(Expr::Literal(Literal::Nil, self.position()))
         }
+        else if self.match_1(TokenTy::Super) {
+            self.consume(TokenTy::Dot, "Expected `.` after `super`.");
+            let method = self.consume_identifier(
+                "Expected superclass method name."
+            );
+            Box::new(Expr::Super(Expr::next_id(), method, self.position()))
+        }
+        else if self.match_1(TokenTy::This) {
+            Box::new(Expr::This(Expr::next_id(), self.position()))
+        }
         else if self.match_2(
             TokenTy::Number(0.0),
             TokenTy::String("".to_string())
@@ -207,6 +565,9 @@ impl Parser {
                 _ => panic!()
             }
         }
+        else if self.match_1(TokenTy::Interpolation("".to_string())) {
+            self.interpolation()
+        }
         else if self.match_1(TokenTy::LeftParen) {
             let expr = self.expression();
             self.consume(
@@ -217,26 +578,98 @@ impl Parser {
         } else if self.match_1(TokenTy::Identifier("".to_string())) {
             match self.previous().ty {
                 TokenTy::Identifier(value) => {
-                    Box::new(Expr::Variable(value, self.position()))
+                    Box::new(Expr::Variable(
+                        value,
+                        Expr::next_id(),
+                        self.position()
+                    ))
                 },
                 _ => panic!()
             }
         } else {
+            if self.check(&TokenTy::Error) {
+                self.panicking = true;
+            }
             Box::new(Expr::Literal(Literal::Nil, self.position()))
         }
     }
 
+    fn interpolation(&mut self) -> Box<Expr> {
+        let start = self.position();
+        let mut parts = Vec::new();
+
+        loop {
+            if let TokenTy::Interpolation(segment) | TokenTy::String(segment) =
+                self.previous().ty
+            {
+                if !segment.is_empty() {
+                    parts.push(Box::new(Expr::Literal(
+                        Literal::String(segment),
+                        self.position()
+                    )));
+                }
+            }
+            if let TokenTy::String(_) = self.previous().ty {
+                break;
+            }
+
+            let next = self.peek().position.offset;
+            let empty = self.source_map.source()
+                                       .get(next..)
+                                       .is_some_and(|rest| rest.starts_with('}'));
+            if empty {
+                let start = self.previous().position.end() - 2;
+                let position = self.source_map.position(next + 1 - start, start);
+                self.error(
+                    ErrorCode::ExpectedExpression,
+                    "Expected expression in interpolation.",
+                    position
+                );
+            } else {
+                parts.push(self.expression());
+            }
+
+            if !self.match_2(
+                TokenTy::Interpolation("".to_string()),
+                TokenTy::String("".to_string())
+            ) {
+                let position = self.position();
+                self.error(
+                    ErrorCode::ExpectedToken,
+                    "Expected `}` after interpolated expression.",
+                    position
+                );
+                break;
+            }
+        }
+
+        Box::new(Expr::Interpolation(parts, start.join(self.position())))
+    }
+
     fn consume(&mut self, ty: TokenTy, message: &str) -> Token {
         if self.check(&ty) {
             self.advance()
         } else {
-            if self.error.is_none() {
-                self.error = Some(message.to_string());
-            }
+            let position = self.previous().position;
+            self.error(ErrorCode::ExpectedToken, message, position);
             self.peek()
         }
     }
 
+    fn consume_identifier(&mut self, message: &str) -> String {
+        match self.consume(TokenTy::Identifier("".to_string()), message).ty {
+            TokenTy::Identifier(name) => name,
+            _ => "".to_string()
+        }
+    }
+
+    fn error(&mut self, code: ErrorCode, message: &str, position: Position) {
+        if !self.panicking && self.peek().ty != TokenTy::Error {
+            self.errors.push((code, message.to_string(), position));
+        }
+        self.panicking = true;
+    }
+
     fn match_many(&mut self, tys: &[TokenTy]) -> bool {
         for ty in tys {
             if self.check(ty) {
@@ -244,7 +677,7 @@ impl Parser {
                 return true;
             }
         }
-        return false
+        false
     }
 
     fn match_1(&mut self, a: TokenTy) -> bool {
@@ -263,7 +696,7 @@ impl Parser {
         if self.is_at_end() {
             false
         } else {
-            discriminant(&self.peek().ty) == discriminant(&token_ty)
+            discriminant(&self.peek().ty) == discriminant(token_ty)
         }
     }
 
@@ -274,8 +707,9 @@ impl Parser {
         self.previous()
     }
 
+
     fn is_at_end(&self) -> bool {
-        self.error.is_some() || self.peek().ty == TokenTy::Eof
+        self.panicking || self.peek().ty == TokenTy::Eof
     }
 
     fn peek(&self) -> Token {
@@ -294,7 +728,10 @@ impl Parser {
     fn get_token(&self, index: usize) -> Token {
         match self.tokens.get(index) {
             Some(token) => token.clone(),
-            None => Token::new(TokenTy::Eof, Position::new(0, self.curr))
+            None => {
+                let end = self.source_map.source().len();
+                Token::new(TokenTy::Eof, self.source_map.position(0, end))
+            }
         }
     }
 
@@ -322,4 +759,185 @@ mod tests {
     fn it_parses_empty_grouping() {
         assert_parse_ok!("();");
     }
+
+    #[test]
+    fn it_parses_assignment() {
+        assert_parse_ok!("var a; var b; a = b = 1;");
+    }
+
+    #[test]
+    fn it_rejects_invalid_assignment_target() {
+        let source = "var a; 1 + 2 = 3;";
+        let mut parser = Parser::new(
+            Scanner::new(source).scan_tokens().expect(""),
+            source
+        );
+        let errs = parser.parse().expect_err("");
+
+        assert_eq!(errs[0].message, "Invalid assignment target.");
+        assert_eq!(errs[0].position, Position::new(5, 7));
+    }
+
+    #[test]
+    fn it_parses_if_else() {
+        assert_parse_ok!("if (true) print 1; else if (false) print 2; else { print 3; }");
+    }
+
+    #[test]
+    fn it_parses_while() {
+        assert_parse_ok!("var i = 0; while (i < 3) i = i + 1;");
+    }
+
+    #[test]
+    fn it_parses_for() {
+        assert_parse_ok!("for (var i = 0; i < 3; i = i + 1) print i;");
+        assert_parse_ok!("var i; for (i = 0; i < 3;) { i = i + 1; }");
+        assert_parse_ok!("for (;;) {}");
+    }
+
+    #[test]
+    fn it_parses_logical_operators() {
+        assert_parse_ok!("var a = nil or 1 and 2 == 2;");
+    }
+
+    #[test]
+    fn it_parses_functions() {
+        assert_parse_ok!("fun f() {} fun g(a, b) { return a + b; } f(); g(1, 2)(3);");
+    }
+
+    #[test]
+    fn it_rejects_too_many_arguments() {
+        let arguments = vec!["1"; 256].join(", ");
+        let source = format!("f({});", arguments);
+        let mut parser = Parser::new(
+            Scanner::new(&source).scan_tokens().expect(""),
+            &source
+        );
+        let errs = parser.parse().expect_err("");
+
+        assert_eq!(errs[0].message, "Cannot have more than 255 arguments.");
+    }
+
+    #[test]
+    fn it_rejects_missing_variable_name() {
+        let source = "var;";
+        let mut parser = Parser::new(
+            Scanner::new(source).scan_tokens().expect(""),
+            source
+        );
+
+        assert!(parser.parse().is_err());
+    }
+
+    #[test]
+    fn it_parses_classes() {
+        assert_parse_ok!("
+            class Point {
+                init(x, y) { this.x = x; this.y = y; }
+                sum() { return this.x + this.y; }
+            }
+            var p = Point(1, 2);
+            p.x = p.sum();
+            print p.x;
+        ");
+    }
+
+    #[test]
+    fn it_parses_inheritance() {
+        assert_parse_ok!("
+            class A { f() { return 1; } }
+            class B < A { f() { return super.f() + 1; } }
+        ");
+    }
+
+    #[test]
+    fn it_reports_multiple_errors() {
+        let source = "
+            var = 1;
+            print (1;
+            var ok = 2;
+            while ok) print ok;
+            class A { m() { var = 1; } }
+            print ok;
+        ";
+        let mut parser = Parser::new(
+            Scanner::new(source).scan_tokens().expect(""),
+            source
+        );
+        let messages: Vec<String> = parser.parse()
+                                          .expect_err("")
+                                          .iter()
+                                          .map(|err| err.message.to_string())
+                                          .collect();
+
+        assert_eq!(messages, vec![
+            "Expected variable name.",
+            "Expected `)` after expression.",
+            "Expected `(` after `while`.",
+            "Expected variable name."
+        ]);
+    }
+
+    #[test]
+    fn it_parses_past_lexical_errors() {
+        let source = "var a = 1 @ 2; print a; var = 1; print \"a";
+        let mut parser = Parser::new(Scanner::new(source).scan(), source);
+        let messages: Vec<String> = parser.parse()
+                                          .expect_err("")
+                                          .iter()
+                                          .map(|err| err.message.to_string())
+                                          .collect();
+
+        assert_eq!(messages, vec!["Expected variable name."]);
+
+        let source = "print 1; # print 2;";
+        let mut parser = Parser::new(Scanner::new(source).scan(), source);
+
+        let stmts = parser.parse().expect("");
+
+        assert_eq!(stmts.len(), 3);
+        assert!(matches!(*stmts[2], Stmt::Print(_, _)));
+    }
+
+    #[test]
+    fn it_parses_interpolations() {
+        assert_parse_ok!("print \"a ${b} c ${d + \"${e}\"}\";");
+        assert_parse_ok!("print \"${a}${b}\";");
+    }
+
+    #[test]
+    fn it_rejects_empty_interpolations() {
+        assert_parse_ok!("print \"${\"x\"}\";");
+
+        let source = "print \"a ${} b\";";
+        let mut parser = Parser::new(Scanner::new(source).scan(), source);
+        let errs = parser.parse().expect_err("");
+
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].message, "Expected expression in interpolation.");
+        assert_eq!(errs[0].position, Position::new(3, 9));
+    }
+
+    #[test]
+    fn it_allows_a_trailing_expression_in_the_repl() {
+        let source = "var a = 1; a + 2";
+        let mut parser = Parser::new(Scanner::new(source).scan(), source);
+        parser.repl = true;
+
+        assert_eq!(parser.parse().map(|stmts| stmts.len()).ok(), Some(2));
+
+        let mut parser = Parser::new(Scanner::new(source).scan(), source);
+        let messages: Vec<String> = parser.parse()
+                                          .expect_err("")
+                                          .iter()
+                                          .map(|err| err.message.to_string())
+                                          .collect();
+
+        assert_eq!(messages, vec!["Expected `;` after expression."]);
+    }
+
+    #[test]
+    fn it_parses_nested_blocks() {
+        assert_parse_ok!("{ var a = 1; { var b = a; print b; } }");
+    }
 }
@@ -1,11 +1,46 @@
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug)]
 pub struct Position {
     pub length: usize,
-    pub offset: usize
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize
 }
 
 impl Position {
     pub fn new(length: usize, offset: usize) -> Position {
-        Position { length, offset }
+        Position {
+            length,
+            offset,
+            line: 0,
+            column: 0,
+            end_line: 0,
+            end_column: 0
+        }
+    }
+
+    pub fn end(&self) -> usize {
+        self.offset + self.length
+    }
+
+    pub fn join(&self, other: Position) -> Position {
+        let start = if other.offset < self.offset { other } else { *self };
+        let end = if other.end() > self.end() { other } else { *self };
+
+        Position {
+            length: end.end() - start.offset,
+            end_line: end.end_line,
+            end_column: end.end_column,
+            ..start
+        }
+    }
+}
+
+// Lines and columns are derived from the offset, so two spans of the same
+// source are equal whenever their offsets and lengths are.
+impl PartialEq for Position {
+    fn eq(&self, other: &Position) -> bool {
+        self.offset == other.offset && self.length == other.length
     }
 }
@@ -0,0 +1,457 @@
+use lox::*;
+use std::collections::HashMap;
+use syntax::*;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FunctionTy {
+    None,
+    Function,
+    Initializer,
+    Method
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ClassTy {
+    None,
+    Class,
+    Subclass
+}
+
+pub struct Resolver {
+    scopes: Vec<HashMap<String, (bool, Position)>>,
+    locals: HashMap<usize, usize>,
+    current_function: FunctionTy,
+    current_class: ClassTy,
+    pub source_file: Option<String>,
+    error: Option<Diagnostic>
+}
+
+impl Resolver {
+    pub fn new() -> Resolver {
+        Resolver {
+            scopes: Vec::new(),
+            locals: HashMap::new(),
+            current_function: FunctionTy::None,
+            current_class: ClassTy::None,
+            source_file: None,
+            error: None
+        }
+    }
+
+    pub fn resolve(
+        &mut self,
+        stmts: &[Box<Stmt>]
+    ) -> Result<HashMap<usize, usize>, Box<Diagnostic>> {
+        self.resolve_stmts(stmts);
+
+        match self.error.take() {
+            Some(diagnostic) => Err(Box::new(diagnostic)),
+            None => Ok(self.locals.clone())
+        }
+    }
+
+    fn resolve_stmts(&mut self, stmts: &[Box<Stmt>]) {
+        for stmt in stmts {
+            self.visit_stmt(stmt);
+        }
+    }
+
+    fn resolve_function(
+        &mut self,
+        params: &[(String, Position)],
+        body: &[Box<Stmt>],
+        ty: FunctionTy
+    ) {
+        let enclosing_function = self.current_function;
+        self.current_function = ty;
+
+        self.begin_scope();
+        for (param, position) in params {
+            self.declare(param, *position);
+            self.define(param, *position);
+        }
+        self.resolve_stmts(body);
+        self.end_scope();
+
+        self.current_function = enclosing_function;
+    }
+
+    fn resolve_local(&mut self, name: &str, id: usize) {
+        for (depth, scope) in self.scopes.iter().rev().enumerate() {
+            if scope.contains_key(name) {
+                self.locals.insert(id, depth);
+                return;
+            }
+        }
+    }
+
+    fn begin_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    fn end_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn declare(&mut self, name: &str, position: Position) {
+        let declared = match self.scopes.last_mut() {
+            Some(scope) => scope.insert(name.to_string(), (false, position)),
+            None => None
+        };
+
+        if let Some((_, previous)) = declared {
+            if self.error.is_none() {
+                let diagnostic = self.diagnostic(
+                    ErrorCode::DuplicateDeclaration,
+                    &format!("Variable `{}` already declared in this scope.", name),
+                    position
+                );
+                self.error = Some(
+                    diagnostic.with_label("previously declared here", previous)
+                );
+            }
+        }
+    }
+
+    fn define(&mut self, name: &str, position: Position) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), (true, position));
+        }
+    }
+
+    fn diagnostic(
+        &self,
+        code: ErrorCode,
+        message: &str,
+        position: Position
+    ) -> Diagnostic {
+        Diagnostic::new(
+            LoxErrorTy::Syntax,
+            code,
+            message,
+            &self.source_file,
+            position
+        )
+    }
+
+    fn error(&mut self, code: ErrorCode, message: &str, position: Position) {
+        if self.error.is_none() {
+            self.error = Some(self.diagnostic(code, message, position));
+        }
+    }
+}
+
+impl Visitor<()> for Resolver {
+    fn visit_stmt(&mut self, s: &Stmt) {
+        match s {
+            Stmt::Block(stmts, _) => {
+                self.begin_scope();
+                self.resolve_stmts(stmts);
+                self.end_scope();
+            },
+            Stmt::Class(name, name_position, superclass, methods, _) => {
+                let enclosing_class = self.current_class;
+                self.current_class = ClassTy::Class;
+
+                self.declare(name, *name_position);
+                self.define(name, *name_position);
+
+                if let Some(superclass) = superclass {
+                    if let Expr::Variable(superclass_name, _, position) = &**superclass {
+                        if superclass_name == name {
+                            self.error(
+                                ErrorCode::InheritsFromItself,
+                                "A class cannot inherit from itself.",
+                                *position
+                            );
+                        }
+                    }
+
+                    self.current_class = ClassTy::Subclass;
+                    self.visit_expr(superclass);
+
+                    self.begin_scope();
+                    self.define("super", *name_position);
+                }
+
+                self.begin_scope();
+                self.define("this", *name_position);
+                for method in methods {
+                    if let Stmt::Function(name, _, params, body, _) = &**method {
+                        let ty = if name == "init" {
+                            FunctionTy::Initializer
+                        } else {
+                            FunctionTy::Method
+                        };
+                        self.resolve_function(params, body, ty);
+                    }
+                }
+                self.end_scope();
+
+                if superclass.is_some() {
+                    self.end_scope();
+                }
+
+                self.current_class = enclosing_class;
+            },
+            Stmt::Expr(expression, _) => {
+                self.visit_expr(expression);
+            },
+            Stmt::Function(name, position, params, body, _) => {
+                self.declare(name, *position);
+                self.define(name, *position);
+                self.resolve_function(params, body, FunctionTy::Function);
+            },
+            Stmt::If(condition, then_branch, else_branch, _) => {
+                self.visit_expr(condition);
+                self.visit_stmt(then_branch);
+                if let Some(else_branch) = else_branch {
+                    self.visit_stmt(else_branch);
+                }
+            },
+            Stmt::Print(expression, _) => {
+                self.visit_expr(expression);
+            },
+            Stmt::Return(value, position) => {
+                if self.current_function == FunctionTy::None {
+                    self.error(
+                        ErrorCode::TopLevelReturn,
+                        "Cannot return from top-level code.",
+                        *position
+                    );
+                }
+                if let Some(expr) = value {
+                    if self.current_function == FunctionTy::Initializer {
+                        self.error(
+                            ErrorCode::InitializerReturn,
+                            "Cannot return a value from an initializer.",
+                            *position
+                        );
+                    }
+                    self.visit_expr(expr);
+                }
+            },
+            Stmt::Var(name, position, initializer, _) => {
+                self.declare(name, *position);
+                if let Some(expr) = initializer {
+                    self.visit_expr(expr);
+                }
+                self.define(name, *position);
+            },
+            Stmt::While(condition, body, _) => {
+                self.visit_expr(condition);
+                self.visit_stmt(body);
+            }
+        }
+    }
+
+    fn visit_expr(&mut self, e: &Expr) {
+        match e {
+            Expr::Assign(name, id, value, _) => {
+                self.visit_expr(value);
+                self.resolve_local(name, *id);
+            },
+            Expr::Binary(left, _, right, _) => {
+                self.visit_expr(left);
+                self.visit_expr(right);
+            },
+            Expr::Call(callee, arguments, _) => {
+                self.visit_expr(callee);
+                for argument in arguments {
+                    self.visit_expr(argument);
+                }
+            },
+            Expr::Get(object, _, _) => {
+                self.visit_expr(object);
+            },
+            Expr::Grouping(expression, _) => {
+                self.visit_expr(expression);
+            },
+            Expr::Interpolation(parts, _) => {
+                for part in parts {
+                    self.visit_expr(part);
+                }
+            },
+            Expr::Literal(_, _) => {},
+            Expr::Logical(left, _, right, _) => {
+                self.visit_expr(left);
+                self.visit_expr(right);
+            },
+            Expr::Set(object, _, value, _) => {
+                self.visit_expr(value);
+                self.visit_expr(object);
+            },
+            Expr::Super(id, _, position) => {
+                match self.current_class {
+                    ClassTy::None => self.error(
+                        ErrorCode::InvalidSuper,
+                        "Cannot use `super` outside of a class.",
+                        *position
+                    ),
+                    ClassTy::Class => self.error(
+                        ErrorCode::InvalidSuper,
+                        "Cannot use `super` in a class with no superclass.",
+                        *position
+                    ),
+                    ClassTy::Subclass => {}
+                }
+                self.resolve_local("super", *id);
+            },
+            Expr::This(id, position) => {
+                if self.current_class == ClassTy::None {
+                    self.error(
+                        ErrorCode::InvalidThis,
+                        "Cannot use `this` outside of a class.",
+                        *position
+                    );
+                }
+                self.resolve_local("this", *id);
+            },
+            Expr::Unary(_, right, _) => {
+                self.visit_expr(right);
+            },
+            Expr::Variable(name, id, position) => {
+                let initializing = match self.scopes.last() {
+                    Some(scope) => matches!(scope.get(name), Some((false, _))),
+                    None => false
+                };
+
+                if initializing {
+                    self.error(
+                        ErrorCode::ReadInInitializer,
+                        "Cannot read local variable in its own initializer.",
+                        *position
+                    );
+                }
+                self.resolve_local(name, *id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve(source: &str) -> Result<HashMap<usize, usize>, Box<Diagnostic>> {
+        let mut parser = Parser::new(
+            Scanner::new(source).scan_tokens().expect(""),
+            source
+        );
+        let stmts = parser.parse().expect("");
+        let mut resolver = Resolver::new();
+        resolver.resolve(&stmts)
+    }
+
+    #[test]
+    fn it_resolves_locals() {
+        let locals = resolve("
+            var a = 1;
+            { var b = a; { print b; } }
+        ").expect("");
+
+        assert_eq!(locals.values().cloned().collect::<Vec<usize>>(), vec![1]);
+    }
+
+    #[test]
+    fn it_resolves_closures() {
+        let locals = resolve("
+            fun outer() { var x = 1; fun inner() { return x; } return inner; }
+        ").expect("");
+        let mut depths: Vec<usize> = locals.values().cloned().collect();
+        depths.sort();
+
+        assert_eq!(depths, vec![0, 1]);
+    }
+
+    #[test]
+    fn it_rejects_reading_local_in_own_initializer() {
+        assert_eq!(
+            resolve("var a = 1; { var a = a; }").expect_err("").message,
+            "Cannot read local variable in its own initializer."
+        );
+    }
+
+    #[test]
+    fn it_rejects_duplicate_declarations() {
+        assert_eq!(
+            resolve("{ var a = 1; var a = 2; }").expect_err("").message,
+            "Variable `a` already declared in this scope."
+        );
+        assert_eq!(
+            resolve("fun f(a, a) {}").expect_err("").message,
+            "Variable `a` already declared in this scope."
+        );
+        assert!(resolve("var a = 1; var a = 2;").is_ok());
+    }
+
+    #[test]
+    fn it_labels_previous_declarations() {
+        let diagnostic = resolve("{ var a = 1; var a = 2; }").expect_err("");
+
+        assert_eq!(diagnostic.code, ErrorCode::DuplicateDeclaration);
+        assert_eq!(diagnostic.position, Position::new(1, 17));
+        assert_eq!(diagnostic.labels, vec![Label {
+            message: "previously declared here".to_string(),
+            position: Position::new(1, 6)
+        }]);
+    }
+
+    #[test]
+    fn it_points_at_declaration_names() {
+        let diagnostic = resolve("fun f(a, a) {}").expect_err("");
+        assert_eq!(diagnostic.position, Position::new(1, 9));
+        assert_eq!(diagnostic.labels[0].position, Position::new(1, 6));
+
+        let diagnostic = resolve("{ fun g() {} class g {} }").expect_err("");
+        assert_eq!(diagnostic.position, Position::new(1, 19));
+        assert_eq!(diagnostic.labels[0].position, Position::new(1, 6));
+
+        let diagnostic = resolve("class A { init() { return 1; } }").expect_err("");
+        assert_eq!(diagnostic.position, Position::new(6, 19));
+    }
+
+    #[test]
+    fn it_rejects_this_outside_of_class() {
+        assert_eq!(
+            resolve("fun f() { return this; }").expect_err("").message,
+            "Cannot use `this` outside of a class."
+        );
+        assert!(resolve("class A { f() { return this; } }").is_ok());
+    }
+
+    #[test]
+    fn it_rejects_returning_value_from_initializer() {
+        assert_eq!(
+            resolve("class A { init() { return 1; } }").expect_err("").message,
+            "Cannot return a value from an initializer."
+        );
+        assert!(resolve("class A { init() { return; } }").is_ok());
+    }
+
+    #[test]
+    fn it_rejects_invalid_super() {
+        assert_eq!(
+            resolve("class A < A {}").expect_err("").message,
+            "A class cannot inherit from itself."
+        );
+        assert_eq!(
+            resolve("fun f() { super.f(); }").expect_err("").message,
+            "Cannot use `super` outside of a class."
+        );
+        assert_eq!(
+            resolve("class A { f() { super.f(); } }").expect_err("").message,
+            "Cannot use `super` in a class with no superclass."
+        );
+        assert!(resolve("class A {} class B < A { f() { super.f(); } }").is_ok());
+    }
+
+    #[test]
+    fn it_rejects_top_level_return() {
+        assert_eq!(
+            resolve("return 1;").expect_err("").message,
+            "Cannot return from top-level code."
+        );
+        assert!(resolve("fun f() { return 1; }").is_ok());
+    }
+}
@@ -1,78 +1,129 @@
 use std::collections::HashMap;
 use lox::*;
 use syntax::*;
+use unicode_xid::UnicodeXID;
+
+pub const KEYWORDS: [(&str, TokenTy); 16] = [
+    ("and", TokenTy::And),
+    ("class", TokenTy::Class),
+    ("else", TokenTy::Else),
+    ("false", TokenTy::False),
+    ("for", TokenTy::For),
+    ("fun", TokenTy::Fun),
+    ("if", TokenTy::If),
+    ("nil", TokenTy::Nil),
+    ("or", TokenTy::Or),
+    ("print", TokenTy::Print),
+    ("return", TokenTy::Return),
+    ("super", TokenTy::Super),
+    ("this", TokenTy::This),
+    ("true", TokenTy::True),
+    ("var", TokenTy::Var),
+    ("while", TokenTy::While)
+];
 
 pub struct Scanner {
     tokens: Vec<Token>,
     keywords: HashMap<&'static str, TokenTy>,
     source: String,
+    source_map: SourceMap,
     pub source_file: Option<String>,
     curr: usize,
     prev: usize,
-    error: Option<String>
+    interpolations: Vec<(usize, usize, usize)>,
+    unterminated: bool,
+    errors: Vec<(ErrorCode, String, Position)>
 }
 
 impl Scanner {
     pub fn new(source: &str) -> Scanner {
-        let keywords = [
-            ("and", TokenTy::And),
-            ("class", TokenTy::Class),
-            ("else", TokenTy::Else),
-            ("false", TokenTy::False),
-            ("for", TokenTy::For),
-            ("fun", TokenTy::Fun),
-            ("if", TokenTy::If),
-            ("nil", TokenTy::Nil),
-            ("or", TokenTy::Or),
-            ("print", TokenTy::Print),
-            ("return", TokenTy::Return),
-            ("super", TokenTy::Super),
-            ("this", TokenTy::This),
-            ("true", TokenTy::True),
-            ("var", TokenTy::Var),
-            ("while", TokenTy::While)
-        ].iter().cloned().collect();
+        let keywords = KEYWORDS.iter().cloned().collect();
 
         Scanner {
             source: source.to_string(),
+            source_map: SourceMap::new(source),
             source_file: None,
             keywords,
             tokens: Vec::new(),
             curr: 0,
             prev: 0,
-            error: None
+            interpolations: Vec::new(),
+            unterminated: false,
+            errors: Vec::new()
         }
     }
 
-    pub fn scan_tokens(&mut self) -> Result<Vec<Token>, LoxError> {
+    pub fn scan_tokens(&mut self) -> Result<Vec<Token>, Vec<Diagnostic>> {
+        let tokens = self.scan();
+
+        if self.errors.is_empty() {
+            Ok(tokens)
+        } else {
+            Err(self.errors())
+        }
+    }
+
+    pub fn scan(&mut self) -> Vec<Token> {
         while !self.at_end() {
             self.scan_token();
         }
 
+        while let Some((_, _, start)) = self.interpolations.pop() {
+            self.unterminated = true;
+            let position = self.source_map.position(2, start);
+            self.report(
+                ErrorCode::UnterminatedInterpolation,
+                "Unterminated string interpolation. Expected `}`",
+                position
+            );
+            self.prev = self.curr;
+            self.push_token(TokenTy::Error);
+        }
+
         self.push_token(TokenTy::Eof);
+        self.tokens.clone()
+    }
 
-        match self.error {
-            Some(ref message) => Err(
-                LoxError::new(
-                    LoxErrorTy::Syntax,
-                    &message,
-                    &self.source,
-                    &self.source_file,
-                    Position::new(1, self.curr - 1)
-                )
-            ),
-            None => Ok(self.tokens.clone())
-        }
+    pub fn is_unterminated(&self) -> bool {
+        self.unterminated
+    }
+
+    pub fn errors(&self) -> Vec<Diagnostic> {
+        self.errors.iter().map(|(code, message, position)| {
+            Diagnostic::new(
+                LoxErrorTy::Syntax,
+                *code,
+                message,
+                &self.source_file,
+                *position
+            )
+        }).collect()
     }
 
-    fn scan_token(&mut self) -> () {
+    fn scan_token(&mut self) {
         self.prev = self.curr;
         match self.next() {
-            '"' => self.scan_string(),
+            '"' => self.scan_string(self.prev),
             '(' => self.push_token(TokenTy::LeftParen),
             ')' => self.push_token(TokenTy::RightParen),
-            '{' => self.push_token(TokenTy::LeftBrace),
-            '}' => self.push_token(TokenTy::RightBrace),
+            '{' => {
+                if let Some((depth, _, _)) = self.interpolations.last_mut() {
+                    *depth += 1;
+                }
+                self.push_token(TokenTy::LeftBrace)
+            },
+            '}' => match self.interpolations.last_mut() {
+                Some((0, start, _)) => {
+                    let start = *start;
+                    self.interpolations.pop();
+                    self.scan_string(start)
+                },
+                Some((depth, _, _)) => {
+                    *depth -= 1;
+                    self.push_token(TokenTy::RightBrace)
+                },
+                None => self.push_token(TokenTy::RightBrace)
+            },
             ',' => self.push_token(TokenTy::Comma),
             '.' => self.push_token(TokenTy::Dot),
             '-' => self.push_token(TokenTy::Minus),
@@ -113,13 +164,15 @@ impl Scanner {
                 },
             c =>
                 if c.is_whitespace() {
-                    return
-                } else if c.is_digit(10) {
+                } else if c.is_ascii_digit() {
                     self.scan_number()
-                } else if c.is_alphabetic() {
+                } else if c == '_' || c.is_xid_start() {
                     self.scan_identifier()
                 } else {
-                    self.stop(&format!("Unexpected character `{}`.", c));
+                    self.error(
+                        ErrorCode::UnexpectedCharacter,
+                        &format!("Unexpected character `{}`.", c)
+                    );
                 }
         }
     }
@@ -129,48 +182,206 @@ impl Scanner {
     }
 
     fn scan_multi_line_comment(&mut self) {
-        while !self.at_end() && !(self.peek_eq('*') && self.peek_next_eq('/')) {
+        while !(self.at_end() || self.peek_eq('*') && self.peek_next_eq('/')) {
             self.next();
         }
 
         if !(self.next_eq('*') && self.next_eq('/')) {
-            self.stop("Unterminated multi-line comment. Expected `*/`");
+            self.unterminated = true;
+            self.error(
+                ErrorCode::UnterminatedComment,
+                "Unterminated multi-line comment. Expected `*/`"
+            );
         }
     }
 
-    fn scan_string(&mut self) {
-        self.skip_until(|c| c == '"');
+    fn scan_string(&mut self, start: usize) {
+        let mut value = String::new();
+        let mut valid = true;
+
+        while !self.at_end() && !self.peek_eq('"') {
+            match self.next() {
+                '\\' => match self.scan_escape() {
+                    Some(c) => value.push(c),
+                    None => valid = false
+                },
+                '$' if self.next_eq('{') => {
+                    self.interpolations.push((0, start, self.curr - 2));
+                    self.push_token(
+                        if valid {
+                            TokenTy::Interpolation(value)
+                        } else {
+                            TokenTy::Error
+                        }
+                    );
+                    return;
+                },
+                c => value.push(c)
+            }
+        }
 
         if !self.next_eq('"') {
-            self.stop("Unterminated string. Expected `\"`");
+            let position = self.source_map.position(1, start);
+            self.unterminated = true;
+            self.report(
+                ErrorCode::UnterminatedString,
+                "Unterminated string. Expected `\"`",
+                position
+            );
+            self.push_token(TokenTy::Error);
+            return;
         }
 
-        let value = self.curr_lexeme().trim_matches('"');
-        let token = TokenTy::String(value.to_string());
+        if valid {
+            self.push_token(TokenTy::String(value));
+        } else {
+            self.push_token(TokenTy::Error);
+        }
+    }
 
-        self.push_token(token);
+    fn scan_escape(&mut self) -> Option<char> {
+        if self.at_end() {
+            return None;
+        }
+
+        let start = self.curr - 1;
+        let c = match self.next() {
+            '"' => Some('"'),
+            '\\' => Some('\\'),
+            '$' => Some('$'),
+            'n' => Some('\n'),
+            't' => Some('\t'),
+            'r' => Some('\r'),
+            '0' => Some('\0'),
+            'u' if self.next_eq('{') => {
+                let digits = self.curr;
+                self.skip_while(|c| c.is_ascii_hexdigit());
+                let hex = self.source[digits..self.curr].to_string();
+
+                if self.next_eq('}') && !hex.is_empty() && hex.len() <= 6 {
+                    u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32)
+                } else {
+                    None
+                }
+            },
+            _ => None
+        };
+
+        if c.is_none() {
+            let escape = &self.source[start..self.curr];
+            let message = if escape.starts_with("\\u") {
+                format!("Invalid Unicode escape sequence `{}`.", escape)
+            } else {
+                format!("Invalid escape sequence `{}`.", escape)
+            };
+            let position = self.source_map.position(self.curr - start, start);
+            self.report(ErrorCode::InvalidEscape, &message, position);
+        }
+
+        c
     }
 
     fn scan_number(&mut self) {
-        self.skip_while(|c| c.is_digit(10));
+        let radix = match (self.curr_lexeme(), self.peek()) {
+            ("0", 'x') | ("0", 'X') => 16,
+            ("0", 'b') | ("0", 'B') => 2,
+            _ => 10
+        };
 
-        if self.peek_eq('.') && self.peek_next().is_digit(10) {
-            self.next();
-            self.skip_while(|c| c.is_digit(10));
+        if radix != 10 {
+            self.scan_radix_number(radix);
+        } else {
+            self.scan_decimal_number();
         }
+    }
+
+    fn scan_radix_number(&mut self, radix: u32) {
+        let name = if radix == 16 { "hexadecimal" } else { "binary" };
 
-        if let Ok(value) = self.curr_lexeme().parse::<f64>() {
-            let token = TokenTy::Number(value);
+        self.next();
+        self.skip_while(|c| c.is_digit(radix) || c == '_');
+
+        if self.peek().is_alphanumeric() {
+            self.skip_while(|c| c.is_alphanumeric() || c == '_');
+            let message = format!(
+                "Invalid {} literal `{}`.",
+                name,
+                self.curr_lexeme()
+            );
+            return self.error(ErrorCode::InvalidNumber, &message);
+        }
 
-            self.push_token(token);
+        let digits = &self.curr_lexeme()[2..];
+        if digits.is_empty() {
+            let message = format!(
+                "Expected {} digits after `{}`.",
+                name,
+                self.curr_lexeme()
+            );
+            return self.error(ErrorCode::InvalidNumber, &message);
         }
+        if !Self::valid_separators(digits, radix) {
+            return self.error(ErrorCode::InvalidNumber, "Digit separators must be between digits.");
+        }
+
+        let value = digits.chars()
+                          .filter_map(|c| c.to_digit(radix))
+                          .fold(0.0, |value, digit| value * radix as f64 + digit as f64);
+
+        self.push_token(TokenTy::Number(value));
     }
 
-    fn scan_identifier(&mut self) {
-        while !self.at_end() && (self.peek().is_alphanumeric() || self.peek_eq('_')) {
+    fn scan_decimal_number(&mut self) {
+        self.skip_while(|c| c.is_ascii_digit() || c == '_');
+
+        if self.peek_eq('.') {
+            if self.peek_next().is_ascii_digit() {
+                self.next();
+                self.skip_while(|c| c.is_ascii_digit() || c == '_');
+            } else if !(self.peek_next() == '_' || self.peek_next().is_xid_start()) {
+                self.next();
+                return self.error(ErrorCode::InvalidNumber, "Expected digits after decimal point.");
+            }
+        }
+
+        if self.peek_eq('e') || self.peek_eq('E') {
             self.next();
+            if self.peek_eq('+') || self.peek_eq('-') {
+                self.next();
+            }
+            if !self.peek().is_ascii_digit() {
+                return self.error(ErrorCode::InvalidNumber, "Expected digits in exponent.");
+            }
+            self.skip_while(|c| c.is_ascii_digit() || c == '_');
+        }
+
+        let lexeme = self.curr_lexeme();
+        if !Self::valid_separators(lexeme, 10) {
+            return self.error(ErrorCode::InvalidNumber, "Digit separators must be between digits.");
         }
 
+        match lexeme.replace('_', "").parse::<f64>() {
+            Ok(value) => self.push_token(TokenTy::Number(value)),
+            Err(_) => {
+                let message = format!("Invalid number literal `{}`.", lexeme);
+                self.error(ErrorCode::InvalidNumber, &message)
+            }
+        }
+    }
+
+    fn valid_separators(digits: &str, radix: u32) -> bool {
+        let chars: Vec<char> = digits.chars().collect();
+
+        chars.iter().enumerate().all(|(i, c)| {
+            *c != '_' ||
+            i > 0 && chars[i - 1].is_digit(radix) &&
+            chars.get(i + 1).is_some_and(|c| c.is_digit(radix))
+        })
+    }
+
+    fn scan_identifier(&mut self) {
+        self.skip_while(|c| c.is_xid_continue());
+
         let value = self.curr_lexeme();
         let token = match self.keywords.get(value) {
             Some(ty) => (*ty).clone(),
@@ -189,22 +400,34 @@ impl Scanner {
                 (length, offset)
             }
         };
-        let token = Token::new(ty, Position::new(length, offset));
+        let token = Token::new(ty, self.source_map.position(length, offset));
 
         self.tokens.push(token);
     }
 
     fn at_end(&self) -> bool {
-        self.error.is_some() || self.curr >= self.source.len()
+        self.curr >= self.source.len()
+    }
+
+    fn error(&mut self, code: ErrorCode, message: &str) {
+        self.push_token(TokenTy::Error);
+
+        let position = self.tokens[self.tokens.len() - 1].position;
+        self.report(code, message, position);
     }
 
-    fn stop(&mut self, message: &str) {
-        self.error = Some(message.to_string());
+    fn report(&mut self, code: ErrorCode, message: &str, position: Position) {
+        self.errors.push((code, message.to_string(), position));
     }
 
     fn next(&mut self) -> char {
-        self.curr += 1;
-        self.nth_char(self.curr - 1)
+        if self.at_end() {
+            return '\0';
+        }
+
+        let c = self.peek();
+        self.curr += c.len_utf8();
+        c
     }
 
     fn next_eq(&mut self, c: char) -> bool {
@@ -217,7 +440,7 @@ impl Scanner {
     }
 
     fn peek(&self) -> char {
-        self.nth_char(self.curr)
+        self.nth_char(0)
     }
 
     fn peek_eq(&self, c: char) -> bool {
@@ -225,7 +448,7 @@ impl Scanner {
     }
 
     fn peek_next(&self) -> char {
-        self.nth_char(self.curr + 1)
+        self.nth_char(1)
     }
 
     fn peek_next_eq(&self, c: char) -> bool {
@@ -233,7 +456,10 @@ impl Scanner {
     }
 
     fn nth_char(&self, n: usize) -> char {
-        self.source.chars().nth(n).unwrap_or('\0')
+        match self.source.get(self.curr..) {
+            Some(rest) => rest.chars().nth(n).unwrap_or('\0'),
+            None => '\0'
+        }
     }
 
     fn curr_lexeme(&self) -> &str {
@@ -257,6 +483,15 @@ impl Scanner {
 mod tests {
     use super::*;
 
+    fn errors(source: &str) -> Vec<(String, Position)> {
+        let mut scanner = Scanner::new(source);
+        scanner.scan();
+        scanner.errors()
+               .into_iter()
+               .map(|err| (err.message, err.position))
+               .collect()
+    }
+
     #[test]
     fn it_scans_delimeters() {
         let mut scanner = Scanner::new("(){},.;");
@@ -324,6 +559,44 @@ mod tests {
         ]));
     }
 
+    #[test]
+    fn it_scans_number_literals() {
+        let mut scanner = Scanner::new("0xFF 0b1010 1e-9 6.02E23 1_000_000 0x_1 0.5e+2 1.5");
+        let tys: Vec<TokenTy> = scanner.scan()
+                                       .into_iter()
+                                       .map(|token| token.ty)
+                                       .collect();
+
+        assert_eq!(tys, vec![
+            TokenTy::Number(255.0),
+            TokenTy::Number(10.0),
+            TokenTy::Number(1e-9),
+            TokenTy::Number(6.02e23),
+            TokenTy::Number(1_000_000.0),
+            TokenTy::Error,
+            TokenTy::Number(50.0),
+            TokenTy::Number(1.5),
+            TokenTy::Eof
+        ]);
+    }
+
+    #[test]
+    fn it_reports_malformed_numbers() {
+        let source = "1. 0x 0b102 1e 1__0 2_ 3.x";
+        let tokens = Scanner::new(source).scan();
+
+        assert_eq!(errors(source), vec![
+            ("Expected digits after decimal point.".to_string(), Position::new(2, 0)),
+            ("Expected hexadecimal digits after `0x`.".to_string(), Position::new(2, 3)),
+            ("Invalid binary literal `0b102`.".to_string(), Position::new(5, 6)),
+            ("Expected digits in exponent.".to_string(), Position::new(2, 12)),
+            ("Digit separators must be between digits.".to_string(), Position::new(4, 15)),
+            ("Digit separators must be between digits.".to_string(), Position::new(2, 20))
+        ]);
+        assert_eq!(tokens[tokens.len() - 4].ty, TokenTy::Number(3.0));
+        assert_eq!(tokens[tokens.len() - 3].ty, TokenTy::Dot);
+    }
+
     #[test]
     fn it_scans_strings() {
         let mut scanner = Scanner::new("\"string\"");
@@ -334,6 +607,87 @@ mod tests {
         ]));
     }
 
+    #[test]
+    fn it_scans_string_escapes() {
+        let mut scanner = Scanner::new(
+            "\"a\\\"b\\\\c\\nd\\te\\rf\\0g\\u{48}\\u{1F980}\" \"multi\nline\""
+        );
+        let tokens = scanner.scan_tokens().expect("");
+
+        assert_eq!(
+            tokens[0].ty,
+            TokenTy::String("a\"b\\c\nd\te\rf\0gH🦀".to_string())
+        );
+        assert_eq!(tokens[1].ty, TokenTy::String("multi\nline".to_string()));
+    }
+
+    #[test]
+    fn it_reports_invalid_escapes() {
+        let source = "\"a\\qb\" \"\\u{D800}\" \"\\u{48\" 1;";
+        let tokens = Scanner::new(source).scan();
+
+        assert_eq!(errors(source), vec![
+            ("Invalid escape sequence `\\q`.".to_string(), Position::new(2, 2)),
+            ("Invalid Unicode escape sequence `\\u{D800}`.".to_string(), Position::new(8, 8)),
+            ("Invalid Unicode escape sequence `\\u{48`.".to_string(), Position::new(5, 19))
+        ]);
+        assert_eq!(tokens[0], Token::new(TokenTy::Error, Position::new(6, 0)));
+        assert_eq!(tokens[1], Token::new(TokenTy::Error, Position::new(10, 7)));
+    }
+
+    #[test]
+    fn it_reports_trailing_backslashes_as_unterminated() {
+        for source in &["\"\\", "\"abc\\"] {
+            let mut scanner = Scanner::new(source);
+            let tokens = scanner.scan();
+
+            assert_eq!(tokens, vec![
+                Token::new(TokenTy::Error, Position::new(source.len(), 0)),
+                Token::new(TokenTy::Eof, Position::new(0, source.len()))
+            ]);
+            assert_eq!(errors(source), vec![
+                ("Unterminated string. Expected `\"`".to_string(), Position::new(1, 0))
+            ]);
+            assert!(scanner.is_unterminated());
+        }
+    }
+
+    #[test]
+    fn it_reports_unterminated_interpolated_strings_at_their_start() {
+        assert_eq!(errors("print \"a ${b} c"), vec![
+            ("Unterminated string. Expected `\"`".to_string(), Position::new(1, 6))
+        ]);
+    }
+
+    #[test]
+    fn it_scans_interpolations() {
+        let mut scanner = Scanner::new("\"a ${b} c ${ {} } d\" \"\\${e}\"");
+        let tys: Vec<TokenTy> = scanner.scan_tokens()
+                                       .expect("")
+                                       .into_iter()
+                                       .map(|token| token.ty)
+                                       .collect();
+
+        assert_eq!(tys, vec![
+            TokenTy::Interpolation("a ".to_string()),
+            TokenTy::Identifier("b".to_string()),
+            TokenTy::Interpolation(" c ".to_string()),
+            TokenTy::LeftBrace,
+            TokenTy::RightBrace,
+            TokenTy::String(" d".to_string()),
+            TokenTy::String("${e}".to_string()),
+            TokenTy::Eof
+        ]);
+    }
+
+    #[test]
+    fn it_reports_unterminated_interpolations() {
+        assert_eq!(errors("\"a ${b"), vec![(
+            "Unterminated string interpolation. Expected `}`".to_string(),
+            Position::new(2, 3)
+        )]);
+    }
+
     #[test]
     fn it_scans_comments() {
         let source = "
@@ -402,4 +756,113 @@ mod tests {
             Token::new(TokenTy::Eof, Position::new(0, 279))
         ]));
     }
+
+    #[test]
+    fn it_scans_unicode() {
+        let source = "var 名前 = \"こんにちは 🦀\"; // 🎉\n/* ✨ */ print café_1 + _x;";
+        let mut scanner = Scanner::new(source);
+        let tokens = scanner.scan_tokens().expect("");
+        let lexemes: Vec<&str> = tokens.iter()
+            .map(|token| &source[token.position.offset..token.position.end()])
+            .collect();
+
+        assert_eq!(tokens[1].ty, TokenTy::Identifier("名前".to_string()));
+        assert_eq!(tokens[3].ty, TokenTy::String("こんにちは 🦀".to_string()));
+        assert_eq!(tokens[6].ty, TokenTy::Identifier("café_1".to_string()));
+        assert_eq!(tokens[8].ty, TokenTy::Identifier("_x".to_string()));
+        assert_eq!(lexemes, vec![
+            "var", "名前", "=", "\"こんにちは 🦀\"", ";",
+            "print", "café_1", "+", "_x", ";", ""
+        ]);
+        assert_eq!(
+            (tokens[5].position.line, tokens[5].position.column),
+            (1, "/* ✨ */ ".chars().count())
+        );
+    }
+
+    #[test]
+    fn it_reports_unexpected_unicode_characters() {
+        let mut scanner = Scanner::new("1 🦀 2");
+        let tokens = scanner.scan();
+
+        assert_eq!(tokens, vec![
+            Token::new(TokenTy::Number(1.0), Position::new(1, 0)),
+            Token::new(TokenTy::Error, Position::new(4, 2)),
+            Token::new(TokenTy::Number(2.0), Position::new(1, 7)),
+            Token::new(TokenTy::Eof, Position::new(0, 8))
+        ]);
+        assert_eq!(
+            scanner.errors()[0].message,
+            "Unexpected character `🦀`."
+        );
+    }
+
+    #[test]
+    fn it_tracks_lines_and_columns() {
+        let mut scanner = Scanner::new("var a;\n  print\n\"b\nc\";");
+        let positions: Vec<(usize, usize, usize, usize)> = scanner.scan()
+            .iter()
+            .map(|token| {
+                let p = token.position;
+                (p.line, p.column, p.end_line, p.end_column)
+            })
+            .collect();
+
+        assert_eq!(positions, vec![
+            (0, 0, 0, 3),
+            (0, 4, 0, 5),
+            (0, 5, 0, 6),
+            (1, 2, 1, 7),
+            (2, 0, 3, 2),
+            (3, 2, 3, 3),
+            (3, 3, 3, 3)
+        ]);
+    }
+
+    #[test]
+    fn it_reports_every_lexical_error() {
+        let source = "@ 1 # \"abc";
+
+        assert_eq!(Scanner::new(source).scan(), vec![
+            Token::new(TokenTy::Error, Position::new(1, 0)),
+            Token::new(TokenTy::Number(1.0), Position::new(1, 2)),
+            Token::new(TokenTy::Error, Position::new(1, 4)),
+            Token::new(TokenTy::Error, Position::new(4, 6)),
+            Token::new(TokenTy::Eof, Position::new(0, 10))
+        ]);
+        assert_eq!(errors(source), vec![
+            ("Unexpected character `@`.".to_string(), Position::new(1, 0)),
+            ("Unexpected character `#`.".to_string(), Position::new(1, 4)),
+            ("Unterminated string. Expected `\"`".to_string(), Position::new(1, 6))
+        ]);
+    }
+
+    #[test]
+    fn it_flags_unterminated_input() {
+        for source in &["\"abc", "/* abc", "\"${a", "\"${ {} "] {
+            let mut scanner = Scanner::new(source);
+            scanner.scan();
+            assert!(scanner.is_unterminated(), "{}", source);
+        }
+
+        let mut scanner = Scanner::new("\"abc\" /* */ @");
+        scanner.scan();
+        assert!(!scanner.is_unterminated());
+    }
+
+    #[test]
+    fn it_reports_unterminated_comments() {
+        let mut scanner = Scanner::new("1 /* comment");
+        let tokens = scanner.scan();
+
+        assert_eq!(tokens, vec![
+            Token::new(TokenTy::Number(1.0), Position::new(1, 0)),
+            Token::new(TokenTy::Error, Position::new(10, 2)),
+            Token::new(TokenTy::Eof, Position::new(0, 12))
+        ]);
+        assert_eq!(
+            scanner.scan_tokens().map_err(|errs| errs.len()),
+            Err(1)
+        );
+    }
 }
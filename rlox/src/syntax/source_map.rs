@@ -0,0 +1,130 @@
+use syntax::*;
+
+pub struct SourceMap {
+    source: String,
+    line_starts: Vec<usize>
+}
+
+impl SourceMap {
+    pub fn new(source: &str) -> SourceMap {
+        let line_starts = Some(0).into_iter()
+                                 .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+                                 .collect();
+
+        SourceMap { source: source.to_string(), line_starts }
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    pub fn line(&self, line: usize) -> &str {
+        let start = match self.line_starts.get(line) {
+            Some(start) => *start,
+            None => return ""
+        };
+        let end = match self.line_starts.get(line + 1) {
+            Some(next) => next - 1,
+            None => self.source.len()
+        };
+        self.source.get(start..end).unwrap_or("")
+    }
+
+    pub fn line_col(&self, offset: usize) -> (usize, usize) {
+        let offset = offset.min(self.source.len());
+        let line = self.line_starts.partition_point(|start| *start <= offset) - 1;
+        let start = self.line_starts[line];
+        let column = self.source[start..].char_indices()
+                                         .take_while(|(i, c)| start + i + c.len_utf8() <= offset)
+                                         .count();
+        (line, column)
+    }
+
+    pub fn offset(&self, line: usize, column: usize) -> Option<usize> {
+        let start = *self.line_starts.get(line)?;
+        let text = self.line(line);
+
+        text.char_indices()
+            .map(|(i, _)| start + i)
+            .chain(Some(start + text.len()))
+            .nth(column)
+    }
+
+    pub fn locate(&self, position: Position) -> Position {
+        let (line, column) = self.line_col(position.offset);
+        let (end_line, end_column) = self.line_col(position.end());
+
+        Position { line, column, end_line, end_column, ..position }
+    }
+
+    pub fn position(&self, length: usize, offset: usize) -> Position {
+        self.locate(Position::new(length, offset))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_maps_offsets_to_lines_and_columns() {
+        let source_map = SourceMap::new("a\nbc\n\nd");
+
+        assert_eq!(source_map.line_col(0), (0, 0));
+        assert_eq!(source_map.line_col(1), (0, 1));
+        assert_eq!(source_map.line_col(2), (1, 0));
+        assert_eq!(source_map.line_col(5), (2, 0));
+        assert_eq!(source_map.line_col(6), (3, 0));
+        assert_eq!(source_map.line_col(7), (3, 1));
+    }
+
+    #[test]
+    fn it_counts_columns_in_characters() {
+        let source_map = SourceMap::new("a\n\"é🦀\" b");
+
+        assert_eq!(source_map.line_col(5), (1, 2));
+        assert_eq!(source_map.line_col(7), (1, 2));
+        assert_eq!(source_map.line_col(9), (1, 3));
+        assert_eq!(source_map.line_col(11), (1, 5));
+        assert_eq!(source_map.offset(1, 3), Some(9));
+        assert_eq!(source_map.offset(1, 6), Some(12));
+        assert_eq!(source_map.offset(1, 7), None);
+    }
+
+    #[test]
+    fn it_maps_lines_and_columns_to_offsets() {
+        let source_map = SourceMap::new("a\nbc\n\nd");
+
+        assert_eq!(source_map.offset(0, 0), Some(0));
+        assert_eq!(source_map.offset(1, 2), Some(4));
+        assert_eq!(source_map.offset(1, 3), None);
+        assert_eq!(source_map.offset(3, 1), Some(7));
+        assert_eq!(source_map.offset(4, 0), None);
+    }
+
+    #[test]
+    fn it_splits_lines() {
+        let source_map = SourceMap::new("a\nbc\n\nd");
+
+        assert_eq!(source_map.line_count(), 4);
+        assert_eq!(source_map.line(1), "bc");
+        assert_eq!(source_map.line(2), "");
+        assert_eq!(source_map.line(3), "d");
+        assert_eq!(source_map.line(4), "");
+    }
+
+    #[test]
+    fn it_locates_spans() {
+        let source_map = SourceMap::new("print 1;\nprint (1 +\n  2);");
+        let position = source_map.position(9, 15);
+
+        assert_eq!(
+            (position.line, position.column, position.end_line, position.end_column),
+            (1, 6, 2, 4)
+        );
+    }
+}
@@ -1,8 +1,21 @@
+use std::rc::Rc;
 use syntax::*;
 
 #[derive(Debug)]
 pub enum Stmt {
+    Block(Vec<Box<Stmt>>, Position),
+    Class(String, Position, Option<Box<Expr>>, Vec<Box<Stmt>>, Position),
     Expr(Box<Expr>, Position),
+    Function(
+        String,
+        Position,
+        Vec<(String, Position)>,
+        Rc<Vec<Box<Stmt>>>,
+        Position
+    ),
+    If(Box<Expr>, Box<Stmt>, Option<Box<Stmt>>, Position),
     Print(Box<Expr>, Position),
-    Var(String, Option<Box<Expr>>, Position)
+    Return(Option<Box<Expr>>, Position),
+    Var(String, Position, Option<Box<Expr>>, Position),
+    While(Box<Expr>, Box<Stmt>, Position)
 }
@@ -1,3 +1,4 @@
+use std::fmt;
 use syntax::*;
 
 #[derive(Clone, Debug, PartialEq)]
@@ -10,15 +11,19 @@ impl Token {
     pub fn new(ty: TokenTy, position: Position) -> Token {
         Token { ty, position }
     }
+}
 
-    pub fn to_string(&self) -> String {
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self.ty {
-            TokenTy::Minus => "-".to_string(),
-            TokenTy::Plus => "+".to_string(),
-            TokenTy::LeftParen => "(".to_string(),
-            TokenTy::RightParen => ")".to_string(),
-            TokenTy::Star => "*".to_string(),
-            _ => format!("{:?}", self.ty)
+            TokenTy::Minus => write!(f, "-"),
+            TokenTy::Plus => write!(f, "+"),
+            TokenTy::LeftParen => write!(f, "("),
+            TokenTy::RightParen => write!(f, ")"),
+            TokenTy::Star => write!(f, "*"),
+            TokenTy::And => write!(f, "and"),
+            TokenTy::Or => write!(f, "or"),
+            _ => write!(f, "{:?}", self.ty)
         }
     }
 }
@@ -21,6 +21,7 @@ pub enum TokenTy {
   LessEqual,
   Identifier(String),
   String(String),
+  Interpolation(String),
   Number(f64),
   And,
   Class,
@@ -38,5 +39,6 @@ pub enum TokenTy {
   True,
   Var,
   While,
+  Error,
   Eof
 }